@@ -0,0 +1,18 @@
+fn main() {
+    #[cfg(feature = "ffi")]
+    {
+        println!("cargo:rerun-if-changed=src/ffi.rs");
+        println!("cargo:rerun-if-changed=cbindgen.toml");
+
+        // generated into OUT_DIR so that builds don't touch the source tree,
+        // and tests check that include/kingslayer.h still matches it
+        let out_dir = std::env::var("OUT_DIR").expect("OUT_DIR is set by cargo");
+        let config = cbindgen::Config::from_file("cbindgen.toml").expect("invalid cbindgen.toml");
+        cbindgen::Builder::new()
+            .with_config(config)
+            .with_src("src/ffi.rs")
+            .generate()
+            .expect("could not generate the C header")
+            .write_to_file(format!("{out_dir}/kingslayer.h"));
+    }
+}
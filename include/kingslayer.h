@@ -0,0 +1,79 @@
+#ifndef KINGSLAYER_H
+#define KINGSLAYER_H
+
+/* Generated from src/ffi.rs by cbindgen. Do not edit. */
+
+#include <stdbool.h>
+
+// A Game owned by the host, freed with `kingslayer_free`
+typedef struct KingslayerGame KingslayerGame;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Create a Game from a RON world, or the default world if `world` is null.
+//
+// Returns null if the world could not be parsed.
+//
+// # Safety
+//
+// `world` must be null or a valid NUL-terminated string.
+struct KingslayerGame *kingslayer_new(const char *world);
+
+// Free a Game created by `kingslayer_new`.
+//
+// # Safety
+//
+// `game` must be null or a Game from `kingslayer_new` that has not been freed.
+void kingslayer_free(struct KingslayerGame *game);
+
+// Free a string returned by any other function.
+//
+// # Safety
+//
+// `s` must be null or a string from this library that has not been freed.
+void kingslayer_free_string(char *s);
+
+// Give a command to the Game and return the output.
+//
+// Returns null if `input` is not valid UTF-8 or the command could not be carried out.
+//
+// # Safety
+//
+// `game` must be a live Game from `kingslayer_new` and `input` a valid NUL-terminated string.
+char *kingslayer_ask(struct KingslayerGame *game, const char *input);
+
+// Whether the player has died.
+//
+// # Safety
+//
+// `game` must be a live Game from `kingslayer_new`.
+bool kingslayer_is_dead(const struct KingslayerGame *game);
+
+// The most recent error on this thread, or null if there has not been one.
+char *kingslayer_last_error(void);
+
+// Restore the Game from a string returned by `kingslayer_save`.
+//
+// Returns 0 on success and -1 on failure.
+//
+// # Safety
+//
+// `game` must be a live Game from `kingslayer_new` and `save` a valid NUL-terminated string.
+int kingslayer_restore(struct KingslayerGame *game, const char *save);
+
+// Save the Game to a string that can be given to `kingslayer_restore`.
+//
+// Returns null if the Game could not be saved.
+//
+// # Safety
+//
+// `game` must be a live Game from `kingslayer_new`.
+char *kingslayer_save(const struct KingslayerGame *game);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* KINGSLAYER_H */
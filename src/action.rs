@@ -3,6 +3,7 @@ use serde::{Deserialize, Serialize};
 #[derive(Clone, Default, Debug, Deserialize, Serialize)]
 pub(crate) enum Action {
     Again,
+    Answer(String, String),
     Attack(String, String),
     Break(String),
     Clarify(String),
@@ -11,15 +12,18 @@ pub(crate) enum Action {
     Drop(String),
     Eat(String),
     Examine(String),
+    Give(String),
     Hello,
     Help,
     Inventory,
+    Light(String),
     #[default]
     Look,
     Move(String),
     NoVerb,
     Open(String),
     Put(String, String),
+    Say(String, String),
     Sleep,
     Take(String),
     Unknown(String),
@@ -27,6 +31,7 @@ pub(crate) enum Action {
     Walk(String),
     Wear(String),
     Where(String),
+    Write(String, String),
 }
 
 impl Action {
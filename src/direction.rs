@@ -1,22 +1,24 @@
+// the directions of exits, which can also be given as commands to walk that way
+pub(crate) const DIRECTIONS: [&str; 11] = [
+    "north",
+    "south",
+    "east",
+    "west",
+    "northeast",
+    "northwest",
+    "southeast",
+    "southwest",
+    "up",
+    "down",
+    "exit",
+];
+
 pub(crate) trait Direction {
     fn is_direction(&self) -> bool;
 }
 
 impl Direction for str {
     fn is_direction(&self) -> bool {
-        matches!(
-            self,
-            "north"
-                | "south"
-                | "east"
-                | "west"
-                | "northeast"
-                | "northwest"
-                | "southeast"
-                | "southwest"
-                | "up"
-                | "down"
-                | "exit"
-        )
+        DIRECTIONS.contains(&self)
     }
 }
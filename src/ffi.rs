@@ -0,0 +1,204 @@
+//! A C API for embedding Kingslayer in other languages
+//!
+//! Strings returned by these functions are owned by the caller and must be freed with
+//! `kingslayer_free_string`. A panic is reported as an error rather than unwinding into the
+//! host. The header is at `include/kingslayer.h`.
+
+use std::{
+    cell::RefCell,
+    ffi::{c_char, c_int, CStr, CString},
+    panic::{self, AssertUnwindSafe},
+    ptr,
+};
+
+use crate::game::Game;
+
+/// A Game owned by the host, freed with `kingslayer_free`
+pub struct KingslayerGame(Game);
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+fn set_error(message: String) {
+    LAST_ERROR.with(|last| *last.borrow_mut() = CString::new(message).ok());
+}
+
+// the result of f, or failed with the error set if it panics, since unwinding into C aborts
+fn guard<T>(failed: T, f: impl FnOnce() -> T) -> T {
+    panic::catch_unwind(AssertUnwindSafe(f)).unwrap_or_else(|panic| {
+        set_error(
+            panic
+                .downcast_ref::<&str>()
+                .map(|s| s.to_string())
+                .or_else(|| panic.downcast_ref::<String>().cloned())
+                .unwrap_or_else(|| "Kingslayer panicked.".to_owned()),
+        );
+        failed
+    })
+}
+
+// a string that C can free with kingslayer_free_string, with any NUL bytes removed
+fn to_c(s: String) -> *mut c_char {
+    CString::new(s.replace('\0', ""))
+        .unwrap_or_default()
+        .into_raw()
+}
+
+// the borrowed C string as UTF-8, or None with the error set if it is not valid
+unsafe fn from_c<'a>(s: *const c_char) -> Option<&'a str> {
+    if s.is_null() {
+        set_error("Unexpected null string.".to_owned());
+        return None;
+    }
+
+    match CStr::from_ptr(s).to_str() {
+        Ok(s) => Some(s),
+        Err(e) => {
+            set_error(e.to_string());
+            None
+        }
+    }
+}
+
+/// Create a Game from a RON world, or the default world if `world` is null.
+///
+/// Returns null if the world could not be parsed.
+///
+/// # Safety
+///
+/// `world` must be null or a valid NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn kingslayer_new(world: *const c_char) -> *mut KingslayerGame {
+    guard(ptr::null_mut(), || {
+        let game = if world.is_null() {
+            Ok(Game::default())
+        } else {
+            let Some(world) = from_c(world) else {
+                return ptr::null_mut();
+            };
+            world.parse::<Game>().map_err(|e| e.to_string())
+        };
+
+        match game {
+            Ok(game) => Box::into_raw(Box::new(KingslayerGame(game))),
+            Err(e) => {
+                set_error(e);
+                ptr::null_mut()
+            }
+        }
+    })
+}
+
+/// Free a Game created by `kingslayer_new`.
+///
+/// # Safety
+///
+/// `game` must be null or a Game from `kingslayer_new` that has not been freed.
+#[no_mangle]
+pub unsafe extern "C" fn kingslayer_free(game: *mut KingslayerGame) {
+    guard((), || {
+        if !game.is_null() {
+            drop(Box::from_raw(game));
+        }
+    })
+}
+
+/// Free a string returned by any other function.
+///
+/// # Safety
+///
+/// `s` must be null or a string from this library that has not been freed.
+#[no_mangle]
+pub unsafe extern "C" fn kingslayer_free_string(s: *mut c_char) {
+    guard((), || {
+        if !s.is_null() {
+            drop(CString::from_raw(s));
+        }
+    })
+}
+
+/// Give a command to the Game and return the output.
+///
+/// Returns null if `input` is not valid UTF-8 or the command could not be carried out.
+///
+/// # Safety
+///
+/// `game` must be a live Game from `kingslayer_new` and `input` a valid NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn kingslayer_ask(
+    game: *mut KingslayerGame,
+    input: *const c_char,
+) -> *mut c_char {
+    guard(ptr::null_mut(), || match from_c(input) {
+        Some(input) => to_c((*game).0.ask(input)),
+        None => ptr::null_mut(),
+    })
+}
+
+/// Whether the player has died.
+///
+/// # Safety
+///
+/// `game` must be a live Game from `kingslayer_new`.
+#[no_mangle]
+pub unsafe extern "C" fn kingslayer_is_dead(game: *const KingslayerGame) -> bool {
+    guard(false, || (*game).0.is_dead())
+}
+
+/// The most recent error on this thread, or null if there has not been one.
+#[no_mangle]
+pub extern "C" fn kingslayer_last_error() -> *mut c_char {
+    guard(ptr::null_mut(), || {
+        LAST_ERROR.with(|last| {
+            last.borrow()
+                .as_ref()
+                .map_or(ptr::null_mut(), |e| e.clone().into_raw())
+        })
+    })
+}
+
+/// Restore the Game from a string returned by `kingslayer_save`.
+///
+/// Returns 0 on success and -1 on failure.
+///
+/// # Safety
+///
+/// `game` must be a live Game from `kingslayer_new` and `save` a valid NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn kingslayer_restore(
+    game: *mut KingslayerGame,
+    save: *const c_char,
+) -> c_int {
+    guard(-1, || {
+        let Some(save) = from_c(save) else {
+            return -1;
+        };
+
+        match (*game).0.restore_from_str(save) {
+            Ok(()) => 0,
+            Err(e) => {
+                set_error(e.to_string());
+                -1
+            }
+        }
+    })
+}
+
+/// Save the Game to a string that can be given to `kingslayer_restore`.
+///
+/// Returns null if the Game could not be saved.
+///
+/// # Safety
+///
+/// `game` must be a live Game from `kingslayer_new`.
+#[no_mangle]
+pub unsafe extern "C" fn kingslayer_save(game: *const KingslayerGame) -> *mut c_char {
+    guard(ptr::null_mut(), || match (*game).0.save_to_string() {
+        Ok(save) => to_c(save),
+        Err(e) => {
+            set_error(e.to_string());
+            ptr::null_mut()
+        }
+    })
+}
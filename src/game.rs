@@ -1,30 +1,47 @@
 use std::{
     collections::{BTreeMap, HashMap},
-    mem,
+    error, mem,
     str::FromStr,
 };
 
 #[cfg(not(target_arch = "wasm32"))]
 use std::{
-    error,
-    fs::File,
-    io::{self, Write},
+    fs::{self, File},
+    io::{self, BufRead, Write},
 };
 
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    action::Action, container::Container, direction::Direction, item::Item, tokens::Tokens,
+    action::Action,
+    container::Container,
+    direction::Direction,
+    item::Item,
+    player::Player,
+    save::{self, Save, WorldMismatch},
+    tokens::{self, Tokens},
+    transcript,
+    verb::Verb,
 };
 
 macro_rules! find_matches {
-    ($self:ident, $noun:ident, $in:ident) => {
-        $self
+    ($self:ident, $noun:ident, $in:ident) => {{
+        let items = $self
             .items
             .iter()
             .filter(|(_, i)| $self.$in(i) && i.names_contains($noun))
-            .collect::<Vec<_>>()
-    };
+            .collect::<Vec<_>>();
+
+        // the ones meant by an answer to which of them or by a pronoun
+        if items.len() > 1 && items.iter().any(|(loc, _)| $self.chosen.contains(loc)) {
+            items
+                .into_iter()
+                .filter(|(loc, _)| $self.chosen.contains(loc))
+                .collect()
+        } else {
+            items
+        }
+    }};
 }
 
 macro_rules! which {
@@ -35,17 +52,7 @@ macro_rules! which {
             $self.last_command.prep().to_owned(),
             $self.last_command.obj().to_owned(),
         );
-        return format!(
-            "Which {}, {}?",
-            $noun,
-            list_names(
-                &$items
-                    .into_iter()
-                    .map(|(_, i)| i.name())
-                    .collect::<Vec<_>>(),
-                "or"
-            )
-        );
+        return $self.which(&$noun, distinguish(&$items));
     }};
 
     ($self:ident, $verb:expr, $noun:expr, $obj:expr, $items:expr) => {{
@@ -55,17 +62,7 @@ macro_rules! which {
             $self.last_command.prep().to_owned(),
             String::new(),
         );
-        return format!(
-            "Which {}, {}?",
-            $obj,
-            list_names(
-                &$items
-                    .into_iter()
-                    .map(|(_, i)| i.name())
-                    .collect::<Vec<_>>(),
-                "or"
-            )
-        );
+        return $self.which(&$obj, distinguish(&$items));
     }};
 }
 
@@ -114,12 +111,27 @@ macro_rules! find {
 }
 
 macro_rules! do_all {
-    ($self:ident, $verb:expr, $noun:expr, $in:ident, $f:ident) => {{
-        if $noun == "all" {
+    ($self:ident, $verb:expr, $noun:expr, $in:ident, $f:ident) => {
+        do_all!($self, $verb, $noun, "", $in, $f)
+    };
+
+    ($self:ident, $verb:expr, $noun:expr, $from:expr, $in:ident, $f:ident) => {{
+        if let Some(except) = all_except($noun) {
+            let from: &str = $from;
+            let sources = find_matches!($self, from, is_visible)
+                .into_iter()
+                .map(|(loc, _)| loc.to_owned())
+                .collect::<Vec<_>>();
+            if !from.is_empty() && sources.is_empty() {
+                return cant_see_any(from);
+            }
+
             let items = $self
                 .items
                 .iter()
                 .filter(|(_, i)| $self.$in(i) && !i.name().is_empty() && i.try_take()) // TODO: might want to filter by criteria besides try_take
+                .filter(|(_, i)| from.is_empty() || sources.iter().any(|source| i.is_in(source)))
+                .filter(|(_, i)| !except.iter().any(|name| i.names_contains(name)))
                 .map(|(loc, _)| loc.to_owned())
                 .collect::<Vec<_>>();
 
@@ -146,9 +158,60 @@ macro_rules! do_all {
 }
 
 /// A Kingslayer game
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(from = "World")]
 pub struct Game {
     player: String,
+    // the items that can be played with ask_as in a shared world
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    players: Vec<String>,
+    items: HashMap<String, Item>,
+    #[serde(default)]
+    dead: bool,
+    #[serde(default)]
+    last_command: Tokens,
+    #[serde(default)]
+    last_it: String,
+    // the item each pronoun refers to
+    #[serde(default)]
+    pronouns: BTreeMap<String, String>,
+    #[serde(default)]
+    turns: u32,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    verbs: Vec<Verb>,
+    #[serde(skip)]
+    world: String,
+    #[serde(skip)]
+    original: HashMap<String, Item>,
+    #[serde(skip)]
+    others: BTreeMap<String, Player>,
+    #[serde(skip)]
+    inbox: HashMap<String, Vec<String>>,
+    #[serde(skip)]
+    aggro: HashMap<String, String>,
+    // the shortcuts the player has made for commands
+    #[serde(skip)]
+    aliases: BTreeMap<String, String>,
+    // whether to show how each command was understood
+    #[serde(skip)]
+    debug_parse: bool,
+    // the words of the last command that failed and which of them was not understood
+    #[serde(skip)]
+    oops: Option<(Vec<String>, usize)>,
+    // the items the player was last asked to choose between, in the order they were listed
+    #[serde(skip)]
+    choices: Vec<String>,
+    // the items chosen by the answer or pronouns of the command being carried out
+    #[serde(skip)]
+    chosen: Vec<String>,
+}
+
+// the serialized fields of a Game, from which the rest of it is rebuilt
+#[derive(Deserialize)]
+struct World {
+    player: String,
+    #[serde(default)]
+    players: Vec<String>,
     items: HashMap<String, Item>,
     #[serde(default)]
     dead: bool,
@@ -156,11 +219,31 @@ pub struct Game {
     last_command: Tokens,
     #[serde(default)]
     last_it: String,
+    #[serde(default)]
+    pronouns: BTreeMap<String, String>,
+    #[serde(default)]
+    turns: u32,
+    #[serde(default)]
+    verbs: Vec<Verb>,
+}
+
+impl From<World> for Game {
+    // a Game deserialized without its RON source is its own world, identified by its players,
+    // its items in order of id and its verbs
+    fn from(world: World) -> Self {
+        let mut game = Self::with_world(world, String::new());
+        let items = game.items.iter().collect::<BTreeMap<_, _>>();
+        let source =
+            ron::to_string(&(&game.player, &game.players, items, &game.verbs)).unwrap_or_default();
+        game.world = save::fingerprint(&source);
+
+        game
+    }
 }
 
 impl Default for Game {
     fn default() -> Self {
-        ron::from_str(include_str!("world.ron")).unwrap()
+        include_str!("world.ron").parse().unwrap()
     }
 }
 
@@ -176,11 +259,114 @@ impl FromStr for Game {
     /// # }
     /// ```
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        ron::from_str(s)
+        let world: World = ron::from_str(s)?;
+        if let Some(id) = world
+            .players
+            .iter()
+            .find(|id| !world.items.contains_key(*id))
+        {
+            return Err(invalid_world(
+                s,
+                id,
+                format!("There is no item \"{id}\" to be a player."),
+            ));
+        }
+        for verb in &world.verbs {
+            let name = verb.names().first().map_or("", String::as_str);
+            let means = verb.means().to_lowercase();
+            if !means.is_empty()
+                && !tokens::is_verb(&means)
+                && !world.verbs.iter().any(|other| {
+                    other.means().is_empty()
+                        && other.names().iter().any(|n| n.to_lowercase() == means)
+                })
+            {
+                return Err(invalid_world(
+                    s,
+                    verb.means(),
+                    format!(
+                        "There is no verb \"{}\" for \"{name}\" to mean.",
+                        verb.means()
+                    ),
+                ));
+            }
+            if let Some(id) = verb.targets().find(|id| !world.items.contains_key(*id)) {
+                return Err(invalid_world(
+                    s,
+                    id,
+                    format!("There is no item \"{id}\" for the verb \"{name}\"."),
+                ));
+            }
+        }
+        let mut items = world.items.iter().collect::<Vec<_>>();
+        items.sort_by_key(|(id, _)| *id);
+        for (item_id, item) in items {
+            if let Some(id) = item.targets().find(|id| !world.items.contains_key(*id)) {
+                return Err(invalid_world(
+                    s,
+                    id,
+                    format!("There is no item \"{id}\" for a trigger of \"{item_id}\"."),
+                ));
+            }
+        }
+
+        Ok(Self::with_world(world, save::fingerprint(s)))
     }
 }
 
 impl Game {
+    // define, show or remove a shortcut for commands, as in "alias loot = take all and i"
+    fn alias(&mut self, input: &str) -> Option<String> {
+        let input = input.trim();
+        let (command, rest) = input.split_once(char::is_whitespace).unwrap_or((input, ""));
+        let rest = rest.trim();
+
+        Some(match command.to_lowercase().as_str() {
+            "alias" if rest.is_empty() => {
+                if self.aliases.is_empty() {
+                    "You have no aliases.".to_owned()
+                } else {
+                    self.aliases
+                        .iter()
+                        .map(|(name, commands)| format!("{name} = {commands}"))
+                        .collect::<Vec<_>>()
+                        .join("\n")
+                }
+            }
+            "alias" => {
+                let (name, commands) = rest
+                    .split_once('=')
+                    .or_else(|| rest.split_once(char::is_whitespace))
+                    .unwrap_or((rest, ""));
+                let (name, commands) = (name.trim().to_lowercase(), commands.trim());
+
+                if !name.chars().all(char::is_alphanumeric)
+                    || matches!(name.as_str(), "alias" | "unalias" | "and" | "then")
+                {
+                    "An alias must be a single word.".to_owned()
+                } else if commands.is_empty() {
+                    match self.aliases.get(&name) {
+                        Some(commands) => format!("{name} = {commands}"),
+                        None => format!("There is no alias \"{name}\"."),
+                    }
+                } else {
+                    self.aliases.insert(name, commands.to_owned());
+                    "Aliased.".to_owned()
+                }
+            }
+            "unalias" if rest.is_empty() => "What do you want to unalias?".to_owned(),
+            "unalias" => {
+                let name = rest.to_lowercase();
+                if self.aliases.remove(&name).is_some() {
+                    "Unaliased.".to_owned()
+                } else {
+                    format!("There is no alias \"{name}\".")
+                }
+            }
+            _ => return None,
+        })
+    }
+
     /// Parse a string into game actions and return the output.
     /// ```
     /// # use kingslayer::Game;
@@ -188,11 +374,21 @@ impl Game {
     /// println!("{}", game.ask("look around"));
     /// ```
     pub fn ask<S: Into<String>>(&mut self, input: S) -> String {
-        let commands: Vec<_> = input
-            .into()
-            .replace([',', ';', '.'], " and ")
-            .split_whitespace()
-            .map(str::to_lowercase)
+        let input = input.into();
+        if let Some(res) = self.alias(&input) {
+            return res;
+        }
+        if tokens::words(&input) == ["debug", "parse"] {
+            self.debug_parse = !self.debug_parse;
+            return format!(
+                "Parse tracing is {}.",
+                if self.debug_parse { "on" } else { "off" }
+            );
+        }
+
+        let commands: Vec<_> = self
+            .expand(tokens::words(&input), &mut Vec::new())
+            .into_iter()
             .fold(vec![Vec::new()], |mut acc, w| {
                 if w == "and" || w == "then" {
                     acc.push(Vec::new())
@@ -203,16 +399,42 @@ impl Game {
             })
             .into_iter()
             .filter(|v| !v.is_empty())
-            .collect();
+            // "and" carries on the nouns of a command until there is another verb,
+            // as in "put the sword and stick in the box" or "take all but the stick and sword"
+            .fold(Vec::new(), |mut acc: Vec<Vec<String>>, words| {
+                match acc.last_mut() {
+                    Some(last) if last.len() > 1 && self.is_noun_phrase(&words) => {
+                        last.push("and".to_owned());
+                        last.extend(words);
+                    }
+                    _ => acc.push(words),
+                }
+                acc
+            });
+
+        if let Some(first) = commands.first() {
+            if first[0] == "oops" {
+                return self.oops(&first[1..]);
+            }
+        }
+        self.oops = None;
+        self.chosen.clear();
+        let choices = mem::take(&mut self.choices);
 
         let mut res = if let Some(first) = commands.first() {
-            let mut tokens = Tokens::new(first);
+            let mut tokens = Tokens::new(&self.synonyms(first));
 
             if let Action::Clarify(_) = self.last_command.action() {
+                let choice = self.choose(&choices, first);
+
                 // answer question unless issuing a known command
-                if let Action::Unknown(_) = tokens.action() {
-                    let words = if tokens.verb() == "it" {
-                        self.last_it.clone()
+                if choice.is_some() || matches!(tokens.action(), Action::Unknown(_)) {
+                    let words = if let Some(choice) = choice {
+                        let name = self.item(&choice).name().to_owned();
+                        self.chosen.push(choice);
+                        name
+                    } else if let Some(name) = self.referent(tokens.verb()) {
+                        name
                     } else {
                         first.join(" ")
                     };
@@ -235,27 +457,34 @@ impl Game {
                     }
                 }
 
-                self.update_last(tokens.clone());
+                match self.fix_typos(tokens) {
+                    Ok((tokens, note)) => {
+                        self.update_last(tokens.clone());
 
-                match self.parse(tokens.action()) {
-                    Outcome::Active(output) => format!("{}{}", output, self.combat()),
-                    Outcome::Idle(output) => output,
+                        format!("{note}{}", self.run(&tokens))
+                    }
+                    Err(question) => question,
                 }
             } else {
                 let tokens = self.replace_it(tokens);
 
-                // don't update last_it if current action is a question
-                match tokens.action() {
-                    Action::Again | Action::Unknown(_) => (),
-                    Action::Clarify(_) => self.last_command = tokens.clone(),
-                    _ => {
-                        self.update_last(tokens.clone());
-                    }
-                }
+                match self.fix_typos(tokens) {
+                    Ok((tokens, note)) => {
+                        // don't update last_it if current action is a question
+                        match tokens.action() {
+                            Action::Unknown(verb) if self.knows_verb(verb) => {
+                                self.update_last(tokens.clone());
+                            }
+                            Action::Again | Action::Unknown(_) => (),
+                            Action::Clarify(_) => self.last_command = tokens.clone(),
+                            _ => {
+                                self.update_last(tokens.clone());
+                            }
+                        }
 
-                match self.parse(tokens.action()) {
-                    Outcome::Active(output) => format!("{}{}", output, self.combat()),
-                    Outcome::Idle(output) => output,
+                        format!("{note}{}", self.run(&tokens))
+                    }
+                    Err(question) => question,
                 }
             }
         } else {
@@ -268,30 +497,34 @@ impl Game {
             if let Action::Clarify(_) = self.last_command.action() {
                 break;
             } else {
-                let mut tokens = self.replace_it(Tokens::new(words));
+                self.chosen.clear();
+                let mut tokens = self.replace_it(Tokens::new(&self.synonyms(words)));
 
                 // If the verb isn't recognized, try to use the verb of the previous command,
                 // but only if the previous command accepts a noun/obj
                 // i.e.: "take apple and orange" would try "take apple and take orange"
-                if !self.last_command.noun().is_empty() || !self.last_command.obj().is_empty() {
-                    if let Action::Unknown(_) = tokens.action() {
-                        tokens = Tokens::with(
-                            self.last_command.verb().to_owned(),
-                            words.join(" "),
-                            self.last_command.prep().to_owned(),
-                            self.last_command.obj().to_owned(),
-                        );
-                    }
+                // unless it looks more like a misspelled verb than something to take
+                if (!self.last_command.noun().is_empty() || !self.last_command.obj().is_empty())
+                    && self.is_noun_phrase(words)
+                {
+                    tokens = Tokens::with(
+                        self.last_command.verb().to_owned(),
+                        words.join(" "),
+                        self.last_command.prep().to_owned(),
+                        self.last_command.obj().to_owned(),
+                    );
                 }
 
+                let (tokens, note) = match self.fix_typos(tokens) {
+                    Ok(fixed) => fixed,
+                    Err(question) => {
+                        res = format!("{res}\n\n{question}");
+                        continue;
+                    }
+                };
                 self.update_last(tokens.clone());
 
-                match self.parse(tokens.action()) {
-                    Outcome::Active(output) => {
-                        res = format!("{}\n\n{}{}", res, output, self.combat())
-                    }
-                    Outcome::Idle(output) => res = format!("{res}\n\n{output}"),
-                }
+                res = format!("{res}\n\n{note}{}", self.run(&tokens));
             }
         }
 
@@ -316,27 +549,83 @@ impl Game {
         chunks.join("\n")
     }
 
+    /// Parse a string into game actions on behalf of one of the players in a shared world.
+    ///
+    /// The players are the world's `player` and the items listed in its `players`, and the other
+    /// players in the same room will see what each of them does.
+    /// Only the command is carried out as that player; `ask`, `hp` and the rest still act as the
+    /// world's `player`, and `hp_as` and `room_as` tell about the others.
+    /// ```
+    /// # use kingslayer::Game;
+    /// # let mut game: Game = include_str!("world.ron").parse().unwrap();
+    /// println!("{}", game.ask_as("PLAYER", "look around"));
+    /// ```
+    pub fn ask_as<S: Into<String>>(&mut self, player: &str, input: S) -> String {
+        if !self.is_player(player) {
+            return format!("There is no player \"{player}\".");
+        }
+
+        // act as the player only for this command, so everything else stays with the world's player
+        let previous = self.player.clone();
+        self.switch_player(player);
+        let res = if self.dead {
+            "You are dead.".to_owned()
+        } else {
+            self.ask(input)
+        };
+        self.switch_player(&previous);
+
+        res
+    }
+
     fn combat(&mut self) -> String {
-        let mut damage = 0;
-        let mut last = String::new();
-        let res = self.items.iter().fold(String::new(), |acc, (id, ent)| {
-            if ent.is_in(self.player_location()) && ent.is_aggressive() && *id != self.player {
-                damage += ent.damage();
-                last = ent.name().to_owned();
-                format!("{}\n\nThe {} hits you.", acc, ent.name()) // TODO: random different messages
+        let location = self.player_location().to_owned();
+        let enemies = self
+            .items
+            .iter()
+            .filter(|(id, i)| i.is_in(&location) && i.is_aggressive() && **id != self.player)
+            .map(|(id, i)| (id.to_owned(), i.name().to_owned(), i.damage()))
+            .collect::<Vec<_>>();
+
+        let mut res = String::new();
+        for (enemy, name, damage) in enemies {
+            // fight back against whoever attacked last if they are still here, otherwise whoever is acting
+            let target = match self.aggro.get(&enemy) {
+                Some(target)
+                    if self.others.get(target).is_some_and(|p| !p.dead)
+                        && self.item(target).is_in(&location) =>
+                {
+                    target.to_owned()
+                }
+                _ => self.player.clone(),
+            };
+            let target_name = self.item(&target).name().to_owned();
+
+            res = if target == self.player {
+                format!("{res}\n\nThe {name} hits you.") // TODO: random different messages
             } else {
-                acc
-            }
-        });
+                self.tell(&target, format!("The {name} hits you."));
+                format!("{res}\n\nThe {name} hits {target_name}.")
+            };
+            self.tell_others(
+                &location,
+                &format!("The {name} hits {target_name}."),
+                &target,
+            );
+            self.item_mut(&target).hurt(damage);
 
-        if !last.is_empty() {
-            self.last_it = last;
+            if target != self.player && self.item(&target).hp() <= 0 {
+                res = format!("{res}\n\n{target_name} dies.");
+                self.kill_other(&target);
+            }
+            self.refer_to(&enemy);
         }
 
-        let player = self.item_mut(&self.player.to_owned());
-        player.hurt(damage);
-
-        if player.hp() <= 0 {
+        if self.item(&self.player).hp() <= 0 {
+            if !self.dead {
+                let name = self.item(&self.player).name().to_owned();
+                self.tell_others(&location, &format!("{name} dies."), "");
+            }
             self.dead = true;
             format!("{res}\n\nYou die.")
         } else {
@@ -346,50 +635,95 @@ impl Game {
 
     fn attack(&mut self, enemy: &str, weapon: &str) -> String {
         let damage = self.item(weapon).damage();
+        let weapon_name = self.item(weapon).name().to_owned();
 
         let enemy_item = self.item_mut(enemy);
         if enemy_item.hp() > 0 {
             enemy_item.hurt(damage);
 
             let enemy_name = enemy_item.name().to_owned();
-            let dies = if enemy_item.hp() <= 0 {
-                if enemy != self.player {
+            if self.others.contains_key(enemy) {
+                let player = self.item(&self.player).name().to_owned();
+                let location = self.player_location().to_owned();
+
+                self.tell(enemy, format!("{player} hits you with the {weapon_name}."));
+                self.tell_others(
+                    &location,
+                    &format!("{player} hits {enemy_name} with the {weapon_name}."),
+                    enemy,
+                );
+            } else if enemy != self.player {
+                self.aggro.insert(enemy.to_owned(), self.player.clone());
+                self.observe(&format!("hits the {enemy_name} with the {weapon_name}."));
+            }
+
+            let dies = if self.item(enemy).hp() <= 0 {
+                if self.others.contains_key(enemy) {
+                    self.kill_other(enemy);
+                } else if enemy != self.player {
                     self.items.remove(enemy);
                 }
 
                 let player_location = self.player_location().to_owned();
                 let loot = self
                     .items
-                    .values_mut()
-                    .filter(|i| i.is_in(enemy))
-                    .map(|i| {
+                    .iter_mut()
+                    .filter(|(_, i)| i.is_in(enemy))
+                    .map(|(loc, i)| {
                         i.set_location(player_location.clone());
-                        i.name()
+                        loc.to_owned()
                     })
                     .collect::<Vec<_>>();
 
                 if loot.is_empty() {
                     " It dies.".to_owned() // TODO: death message
                 } else {
-                    if loot.len() == 1 {
-                        self.last_it = loot[0].to_owned();
+                    if let [item] = &loot[..] {
+                        self.refer_to(item);
                     }
-                    format!(" It dies. It drops {}.", list_names(&loot, "and"))
+                    let names = loot
+                        .iter()
+                        .map(|loc| self.item(loc).name())
+                        .collect::<Vec<_>>();
+                    format!(" It dies. It drops {}.", list_names(&names, "and"))
                 }
             } else {
                 String::new()
             };
-            format!(
-                "You hit the {} with your {}.{}",
-                enemy_name,
-                self.item(weapon).name(),
-                dies
-            )
+            format!("You hit the {enemy_name} with your {weapon_name}.{dies}")
         } else {
             format!("The {} has no effect.", self.item(weapon).name())
         }
     }
 
+    // the item among the choices that the words of an answer like "the second one" or "north" refer to
+    fn choose(&self, choices: &[String], words: &[String]) -> Option<String> {
+        let words = tokens::normalize(&words.join(" "))
+            .into_iter()
+            .filter(|w| w != "one")
+            .collect::<Vec<_>>();
+
+        if let [word] = &words[..] {
+            if word == "last" {
+                return choices.last().cloned();
+            } else if let Some(n) = tokens::ordinal(word) {
+                return choices.get(n - 1).cloned();
+            }
+        } else if words.is_empty() {
+            return None;
+        }
+
+        let search = words.join(" ");
+        match &choices
+            .iter()
+            .filter(|choice| self.item(choice).names_contains(&search))
+            .collect::<Vec<_>>()[..]
+        {
+            [choice] => Some((*choice).to_owned()),
+            _ => None,
+        }
+    }
+
     fn close(&mut self, location: &str) -> String {
         let item = self.item(location);
         let location = if !item.door().is_empty() {
@@ -398,7 +732,23 @@ impl Game {
             location.to_owned()
         };
 
-        self.item_mut(&location).close()
+        let was_open = self.item(&location).is_open();
+        let res = self.item_mut(&location).close();
+        if was_open && self.item(&location).is_closed() {
+            self.observe(&format!("closes the {}.", self.item(&location).name()));
+        }
+        res
+    }
+
+    /// The ways to finish a partial command, from the known verbs and the names of visible items
+    /// ```
+    /// # use kingslayer::Game;
+    /// # let game = Game::default();
+    /// assert_eq!(game.complete("exa"), ["examine"]);
+    /// assert_eq!(game.complete("take sti"), ["take stick"]);
+    /// ```
+    pub fn complete(&self, input: &str) -> Vec<String> {
+        tokens::complete(input, &self.verb_names(), &self.visible_names())
     }
 
     // print the contents of an item
@@ -443,6 +793,13 @@ impl Game {
         }
     }
 
+    // the verb declared by the world with this name that is not just a synonym
+    fn custom_verb(&self, verb: &str) -> Option<&Verb> {
+        self.verbs
+            .iter()
+            .find(|custom| custom.is_named(verb) && custom.means().is_empty())
+    }
+
     // print self.contents(...) with the item desc
     fn desc_contents(&self, location: &str, item: &Item) -> String {
         let contents = self.contents(location, item, 1);
@@ -460,11 +817,13 @@ impl Game {
         let player_location = self.player_location().to_owned();
 
         self.item_mut(location).set_location(player_location);
+        self.observe(&format!("drops the {}.", self.item(location).name()));
         "Dropped.".to_owned()
     }
 
     fn eat(&mut self, location: &str) -> String {
         if self.item(location).can_eat() {
+            self.observe(&format!("eats the {}.", self.item(location).name()));
             self.items.remove(location);
             "Delicious.".to_owned()
         } else {
@@ -472,8 +831,20 @@ impl Game {
         }
     }
 
+    // let the world react to the player's action
+    fn end_turn(&mut self) -> String {
+        self.turns += 1;
+        self.combat()
+    }
+
     fn examine(&self, location: &str) -> String {
-        self.item(location).details().to_owned()
+        let item = self.item(location);
+
+        match (item.details(), item.writing()) {
+            (details, "") => details.to_owned(),
+            ("", writing) => format!("Written on the {} is \"{writing}\".", item.name()),
+            (details, writing) => format!("{details}\nWritten on it is \"{writing}\"."),
+        }
     }
 
     fn examine_container(&self, location: &str) -> String {
@@ -504,10 +875,117 @@ impl Game {
         }
     }
 
+    // the words with each command that starts with an alias replaced by what it stands for,
+    // leaving an alias alone within its own expansion so that it can't go on forever
+    fn expand(&self, words: Vec<String>, expanding: &mut Vec<String>) -> Vec<String> {
+        let mut expanded = Vec::new();
+        let mut starts_command = true;
+
+        for word in words {
+            let ends_command = word == "and" || word == "then";
+
+            match self.aliases.get(&word) {
+                Some(commands) if starts_command && !expanding.contains(&word) => {
+                    expanding.push(word);
+                    expanded.extend(self.expand(tokens::words(commands), expanding));
+                    expanding.pop();
+                }
+                _ => expanded.push(word),
+            }
+            starts_command = ends_command;
+        }
+
+        expanded
+    }
+
+    // correct a misspelled verb or item name and return a note about the correction,
+    // or a question if the player could have meant more than one thing
+    fn fix_typos(&mut self, tokens: Tokens) -> Result<(Tokens, String), String> {
+        let mut fixed = false;
+
+        let verb = match tokens.action() {
+            Action::NoVerb => return Ok((tokens, String::new())),
+            Action::Unknown(verb) if self.knows_verb(verb) => verb.to_owned(),
+            Action::Unknown(verb) => match tokens::similar_verbs(verb)[..] {
+                [] => return Ok((tokens, String::new())),
+                [similar] => {
+                    fixed = true;
+                    similar.to_owned()
+                }
+                ref verbs => return Err(format!("Did you mean to {}?", verbs.join(" or to "))),
+            },
+            _ => tokens.verb().to_owned(),
+        };
+        let prep = tokens.prep().to_owned();
+
+        let mut nouns = Vec::new();
+        for noun in tokens.nouns() {
+            nouns.push(match self.similar_names(noun)[..] {
+                [] => noun.to_owned(),
+                [ref similar] => {
+                    fixed = true;
+                    similar.to_owned()
+                }
+                ref names => {
+                    self.last_command =
+                        Tokens::with(verb, String::new(), prep, tokens.obj().to_owned());
+                    return Err(did_you_mean(names));
+                }
+            });
+        }
+        let noun = nouns.join(" and ");
+
+        let obj = match self.similar_names(tokens.obj())[..] {
+            [] => tokens.obj().to_owned(),
+            [ref similar] => {
+                fixed = true;
+                similar.to_owned()
+            }
+            ref names => {
+                self.last_command = Tokens::with(verb, noun, prep, String::new());
+                return Err(did_you_mean(names));
+            }
+        };
+
+        if fixed {
+            let tokens = Tokens::with(verb, noun, prep, obj);
+            // a corrected verb on its own is named, as "(taking)" would not say what was meant
+            let note = if tokens.noun().is_empty() && !matches!(tokens.action(), Action::Walk(_)) {
+                format!("[assuming you meant \"{}\"]\n", tokens.verb())
+            } else {
+                format!("({})\n", tokens.describe())
+            };
+            Ok((tokens, note))
+        } else {
+            Ok((tokens, String::new()))
+        }
+    }
+
     fn have_already(&self, location: &str) -> String {
         format!("You already have the {}.", self.item(location).name())
     }
 
+    /// The player's remaining hit points
+    /// ```
+    /// # use kingslayer::Game;
+    /// # let game = Game::default();
+    /// assert_eq!(game.hp(), 10);
+    /// ```
+    pub fn hp(&self) -> i8 {
+        self.item(&self.player).hp()
+    }
+
+    /// The HP of one of the players in a shared world
+    /// ```
+    /// # use kingslayer::Game;
+    /// # let game = Game::default();
+    /// assert_eq!(game.hp_as("PLAYER"), Some(game.hp()));
+    /// assert_eq!(game.hp_as("STICK"), None);
+    /// ```
+    pub fn hp_as(&self, player: &str) -> Option<i8> {
+        self.is_player(player).then(|| self.item(player).hp())
+    }
+
     fn holding(&self, item: &Item) -> bool {
         item.is_in(&self.player)
     }
@@ -546,6 +1024,20 @@ impl Game {
         }
     }
 
+    /// Whether the player has died
+    /// ```
+    /// # use kingslayer::Game;
+    /// # let game = Game::default();
+    /// assert!(!game.is_dead());
+    /// ```
+    pub fn is_dead(&self) -> bool {
+        self.dead
+    }
+
+    fn is_player(&self, id: &str) -> bool {
+        id == self.player || self.others.contains_key(id)
+    }
+
     // is the item visible in the room or in inventory
     fn is_visible(&self, item: &Item) -> bool {
         self.in_inventory(item) || self.in_room(item)
@@ -556,7 +1048,7 @@ impl Game {
     }
 
     fn is_visible_has_details(&self, item: &Item) -> bool {
-        self.is_visible(item) && !item.details().is_empty()
+        self.is_visible(item) && (!item.details().is_empty() || !item.writing().is_empty())
     }
 
     fn is_visible_has_door(&self, item: &Item) -> bool {
@@ -587,11 +1079,65 @@ impl Game {
             }
     }
 
+    // the response of the one visible item named in a command to its verb
+    fn item_response(&self, tokens: &Tokens) -> Option<String> {
+        let noun = tokens.noun();
+        if noun.is_empty() {
+            return None;
+        }
+
+        match find_matches!(self, noun, is_visible)[..] {
+            [(_, item)] => item.response(tokens.verb()).map(str::to_owned),
+            _ => None,
+        }
+    }
+
+    // whether words without a verb of their own look more like the names of things than a misspelled verb
+    fn is_noun_phrase(&self, words: &[String]) -> bool {
+        match Tokens::new(words).action() {
+            Action::Unknown(verb) if !self.knows_verb(verb) => {
+                let noun = words.join(" ");
+                let noun = noun.as_str();
+
+                tokens::similar_verbs(verb).is_empty()
+                    || !find_matches!(self, noun, is_visible).is_empty()
+            }
+            _ => false,
+        }
+    }
+
+    // make every player in the world one of the others, so they see what happens from the start
+    fn join_players(&mut self) {
+        for id in &self.players {
+            if *id != self.player {
+                self.others.entry(id.to_owned()).or_default();
+            }
+        }
+    }
+
+    // whether the world gives a verb meaning, either as a verb or as a response of an item
+    fn knows_verb(&self, verb: &str) -> bool {
+        self.custom_verb(verb).is_some() || self.items.values().any(|i| i.response(verb).is_some())
+    }
+
+    fn kill_other(&mut self, player: &str) {
+        if let Some(other) = self.others.get_mut(player) {
+            other.dead = true;
+        }
+        let location = self.item(player).location().to_owned();
+        let name = self.item(player).name().to_owned();
+
+        self.tell(player, "You die.".to_owned());
+        self.tell_others(&location, &format!("{name} dies."), player);
+    }
+
     fn item_mut(&mut self, key: &str) -> &mut Item {
         self.items.get_mut(key).unwrap()
     }
 
-    /// Load a Game from a savefile
+    /// Load a Game from a savefile made with the built-in world
+    ///
+    /// A whole Game saved by an older version is loaded as it was.
     /// ```
     /// # use kingslayer::Game;
     /// # let game = Game::default();
@@ -600,7 +1146,28 @@ impl Game {
     /// ```
     #[cfg(not(target_arch = "wasm32"))]
     pub fn load(filename: &str) -> Result<Self, Box<dyn error::Error>> {
-        Ok(ron::de::from_reader(&mut File::open(filename)?)?)
+        let save = fs::read_to_string(filename)?;
+        let mut game = Self::default();
+
+        match game.restore_from_str(&save) {
+            Ok(()) => Ok(game),
+            Err(e) => ron::from_str(&save).map_err(|_| e),
+        }
+    }
+
+    /// Load a Game from a RON world and a savefile made with that world
+    /// ```
+    /// # use kingslayer::Game;
+    /// # let game = Game::default();
+    /// # game.save("kingslayer.save");
+    /// Game::load_with(include_str!("world.ron"), "kingslayer.save");
+    /// ```
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn load_with(world: &str, filename: &str) -> Result<Self, Box<dyn error::Error>> {
+        let mut game: Self = world.parse()?;
+        game.restore_from_str(&fs::read_to_string(filename)?)?;
+
+        Ok(game)
     }
 
     fn look(&self) -> String {
@@ -617,8 +1184,16 @@ impl Game {
             self.items
                 .iter()
                 .fold(BTreeMap::new(), |mut acc, (loc, i)| {
-                    if i.is_in(self.player_location()) {
-                        let desc = if i.is_container() {
+                    if i.is_in(self.player_location()) && *loc != self.player {
+                        let desc = if let Some(other) = self.others.get(loc) {
+                            if other.dead {
+                                String::new()
+                            } else if i.desc().is_empty() {
+                                format!("{} is here.", i.name())
+                            } else {
+                                i.desc().to_owned()
+                            }
+                        } else if i.is_container() {
                             self.desc_contents(loc, i)
                         } else {
                             i.desc().to_owned()
@@ -640,13 +1215,26 @@ impl Game {
         )
     }
 
+    /// Take the descriptions of everything a player in a shared world has seen happen since the last call
+    /// ```
+    /// # use kingslayer::Game;
+    /// # let mut game = Game::default();
+    /// for message in game.messages("PLAYER") {
+    ///     println!("{message}");
+    /// }
+    /// ```
+    pub fn messages(&mut self, player: &str) -> Vec<String> {
+        self.inbox.remove(player).unwrap_or_default()
+    }
+
     fn move_item(&mut self, location: &str) -> String {
         let room = self.item(location).location().to_owned();
 
         match self.item_mut(location).move_self() {
             Ok((message, reveals)) => {
-                if reveals.len() == 1 {
-                    self.last_it = self.item(&reveals[0]).name().to_owned();
+                self.observe(&format!("moves the {}.", self.item(location).name()));
+                if let [item] = &reveals[..] {
+                    self.refer_to(item);
                 }
                 for x in reveals {
                     self.item_mut(&x).set_location(room.clone());
@@ -657,11 +1245,41 @@ impl Game {
         }
     }
 
+    fn move_player(&mut self, destination: String) -> String {
+        self.observe("leaves.");
+        self.item_mut(&self.player.clone())
+            .set_location(destination);
+        self.observe("arrives.");
+        self.look()
+    }
+
     fn not_have(&mut self, location: &str) -> String {
-        self.last_it = self.item(location).name().to_owned();
+        self.refer_to(location);
         format!("You do not have the {}.", self.item(location).name())
     }
 
+    // let the other players in the room see what the current player does
+    fn observe(&mut self, action: &str) {
+        if !self.others.is_empty() {
+            let message = format!("{} {action}", self.item(&self.player).name());
+            let location = self.player_location().to_owned();
+            self.tell_others(&location, &message, "");
+        }
+    }
+
+    // reissue the last command that failed with the word that was not understood replaced
+    fn oops(&mut self, words: &[String]) -> String {
+        match (&self.oops, words) {
+            (None, _) => "There is nothing to correct.".to_owned(),
+            (Some(_), []) => "What word did you mean?".to_owned(),
+            (Some((command, i)), words) => {
+                let mut command = command.clone();
+                command.splice(*i..=*i, words.iter().cloned());
+                self.ask(command.join(" "))
+            }
+        }
+    }
+
     fn open(&mut self, location: &str) -> String {
         let item = self.item(location);
         let location = if !item.door().is_empty() {
@@ -672,23 +1290,44 @@ impl Game {
 
         let contents = self
             .items
-            .values()
-            .filter(|i| i.is_in(&location))
-            .map(|i| i.name())
+            .iter()
+            .filter(|(_, i)| i.is_in(&location))
+            .map(|(loc, _)| loc.to_owned())
             .collect::<Vec<_>>();
 
-        if self.item(&location).is_closed() && contents.len() == 1 {
-            self.last_it = contents[0].to_owned();
+        if let [item] = &contents[..] {
+            if self.item(&location).is_closed() {
+                self.refer_to(item);
+            }
         }
-        let reveals = list_names(&contents, "and");
+        let reveals = list_names(
+            &contents
+                .iter()
+                .map(|loc| self.item(loc).name())
+                .collect::<Vec<_>>(),
+            "and",
+        );
 
-        self.item_mut(&location).open(reveals)
+        let was_closed = self.item(&location).is_closed();
+        let res = self.item_mut(&location).open(reveals);
+        if was_closed && self.item(&location).is_open() {
+            self.observe(&format!("opens the {}.", self.item(&location).name()));
+        }
+        res
     }
 
     // TODO: equip
-    fn parse(&mut self, action: &Action) -> Outcome {
-        match action {
+    fn parse(&mut self, tokens: &Tokens) -> Outcome {
+        if let Some(response) = self.item_response(tokens) {
+            return Outcome::Active(response);
+        }
+        if tokens.nouns().len() > 1 {
+            return self.parse_each(tokens);
+        }
+
+        match tokens.action() {
             Action::Again => self.parse_again(),
+            Action::Answer(text, to) => Outcome::Active(self.say("answer", text, to)),
             Action::Attack(noun, obj) => Outcome::Active(self.parse_attack(noun, obj)),
             Action::Break(_) => Outcome::Active("You can't do that yet.".to_owned()),
             Action::Clarify(message) => Outcome::Idle(message.to_owned()),
@@ -696,8 +1335,10 @@ impl Game {
             Action::Close(noun) => Outcome::Active(self.parse_close(noun)),
             Action::Drop(noun) => Outcome::Active(self.parse_drop(noun)),
             Action::Put(noun, obj) => Outcome::Active(self.parse_put(noun, obj)),
+            Action::Say(text, to) => Outcome::Active(self.say("say", text, to)),
             Action::Eat(noun) => Outcome::Active(self.parse_eat(noun)),
             Action::Examine(noun) => Outcome::Active(self.parse_examine(noun)),
+            Action::Give(_) | Action::Light(_) => Outcome::Active("You can't do that.".to_owned()),
             Action::Hello => Outcome::Active("Hello!".to_owned()),
             Action::Help => Outcome::Idle("That would be nice, wouldn't it?".to_owned()),
             Action::Inventory => Outcome::Active(self.inventory()),
@@ -706,12 +1347,16 @@ impl Game {
             Action::NoVerb => Outcome::Active("Excuse me?".to_owned()),
             Action::Open(noun) => Outcome::Active(self.parse_open(noun)),
             Action::Sleep => Outcome::Active("Time passes...".to_owned()),
-            Action::Take(noun) => Outcome::Active(self.parse_take(noun)),
-            Action::Unknown(verb) => Outcome::Idle(format!("I do not know the verb \"{verb}\".")),
+            Action::Take(noun) => Outcome::Active(match tokens.prep() {
+                "from" | "out" => self.parse_take(noun, tokens.obj()),
+                _ => self.parse_take(noun, ""),
+            }),
+            Action::Unknown(verb) => self.parse_custom(verb, tokens),
             Action::Version => Outcome::Idle(format!("Kingslayer {}", env!("CARGO_PKG_VERSION"))),
             Action::Walk(direction) => Outcome::Active(self.parse_walk(direction)),
             Action::Wear(_) => Outcome::Active("You can't do that yet.".to_owned()),
             Action::Where(noun) => Outcome::Active(self.parse_where(noun)),
+            Action::Write(noun, obj) => Outcome::Active(self.parse_write(noun, obj)),
         }
     }
 
@@ -719,7 +1364,7 @@ impl Game {
         if let Action::Again = self.last_command.action() {
             Outcome::Idle("Excuse me?".to_owned())
         } else {
-            self.parse(&self.last_command.action().clone())
+            self.parse(&self.last_command.clone())
         }
     }
 
@@ -734,10 +1379,83 @@ impl Game {
         find!(self, "close", noun, is_visible, close)
     }
 
-    fn parse_drop(&mut self, noun: &str) -> String {
-        do_all!(self, "drop", noun, in_inventory, drop_item);
+    // follow the first rule of a verb declared by the world that fits the words after it
+    fn parse_custom(&mut self, verb: &str, tokens: &Tokens) -> Outcome {
+        if !self.knows_verb(verb) {
+            return Outcome::Idle(format!("I do not know the verb \"{verb}\"."));
+        }
 
-        find!(self, "drop", noun, in_inventory, drop_item);
+        let mut words = tokens::normalize(tokens.noun());
+        if !tokens.prep().is_empty() {
+            words.push(tokens.prep().to_owned());
+        }
+        words.extend(tokens::normalize(tokens.obj()));
+
+        let mut unseen = None;
+        let rules = self.custom_verb(verb).map_or(&[][..], Verb::rules);
+        let rule = rules.iter().find(|rule| {
+            let Some(captures) = rule.captures(&words) else {
+                return false;
+            };
+
+            captures.iter().all(|(noun, required)| {
+                let noun = noun.as_str();
+                let items = find_matches!(self, noun, is_visible);
+
+                if items.is_empty() {
+                    unseen.get_or_insert_with(|| noun.to_owned());
+                }
+                if required.is_empty() {
+                    !items.is_empty()
+                } else {
+                    items.iter().any(|(id, _)| *id == required)
+                }
+            })
+        });
+
+        let Some(rule) = rule.cloned() else {
+            let noun = tokens.noun();
+            if unseen.is_none()
+                && !noun.is_empty()
+                && find_matches!(self, noun, is_visible).is_empty()
+            {
+                unseen = Some(noun.to_owned());
+            }
+
+            return Outcome::Idle(match unseen {
+                Some(noun) => cant_see_any(&noun),
+                None if words.is_empty() => format!("What do you want to {verb}?"),
+                None => format!("You cannot {verb} that."),
+            });
+        };
+
+        for (item, location) in &rule.moves {
+            if let Some(item) = self.items.get_mut(item) {
+                item.set_location(location.to_owned());
+            }
+        }
+
+        let mut output = rule.response;
+        if !rule.go.is_empty() {
+            let look = self.move_player(rule.go);
+            output = if output.is_empty() {
+                look
+            } else {
+                format!("{output}\n\n{look}")
+            };
+        }
+
+        Outcome::Active(if output.is_empty() {
+            "Done.".to_owned()
+        } else {
+            output
+        })
+    }
+
+    fn parse_drop(&mut self, noun: &str) -> String {
+        do_all!(self, "drop", noun, in_inventory, drop_item);
+
+        find!(self, "drop", noun, in_inventory, drop_item);
 
         find!(self, "drop", noun, is_visible, not_have)
     }
@@ -746,6 +1464,45 @@ impl Game {
         find!(self, "eat", noun, is_visible, eat)
     }
 
+    // carry out a command for each of its nouns, as do_all! does for everything
+    fn parse_each(&mut self, tokens: &Tokens) -> Outcome {
+        let mut active = false;
+        let mut message = Vec::new();
+
+        for noun in tokens.nouns() {
+            let name = match find_matches!(self, noun, is_visible)[..] {
+                [(_, item)] => item.name().to_owned(),
+                _ => noun.to_owned(),
+            };
+
+            let output = match self.parse(&Tokens::with(
+                tokens.verb().to_owned(),
+                noun.to_owned(),
+                tokens.prep().to_owned(),
+                tokens.obj().to_owned(),
+            )) {
+                Outcome::Active(output) => {
+                    active = true;
+                    output
+                }
+                Outcome::Idle(output) => output,
+            };
+            message.push(format!("{name}: {output}"));
+
+            // stop to answer a question about one of them
+            if let Action::Clarify(_) = self.last_command.action() {
+                break;
+            }
+        }
+
+        let message = message.join("\n");
+        if active {
+            Outcome::Active(message)
+        } else {
+            Outcome::Idle(message)
+        }
+    }
+
     // TODO: account for a container also having details?
     fn parse_examine(&mut self, noun: &str) -> String {
         find!(self, "examine", noun, is_visible_has_details, examine);
@@ -768,8 +1525,8 @@ impl Game {
         find!(self, "put", noun, is_visible, not_have)
     }
 
-    fn parse_take(&mut self, noun: &str) -> String {
-        do_all!(self, "take", noun, is_visible_not_holding, take);
+    fn parse_take(&mut self, noun: &str, from: &str) -> String {
+        do_all!(self, "take", noun, from, is_visible_not_holding, take);
 
         find!(self, "take", noun, in_room, take);
         find!(self, "take", noun, is_visible_not_holding, take);
@@ -797,26 +1554,151 @@ impl Game {
         }
     }
 
+    fn parse_write(&mut self, noun: &str, obj: &str) -> String {
+        let items = find_matches!(self, obj, is_visible);
+
+        match items[..] {
+            [] => cant_see_any(obj),
+            [(item, _)] => {
+                let item = item.to_owned();
+                let text = tokens::unquote(noun);
+                self.observe(&format!("writes on the {}.", self.item(&item).name()));
+                self.item_mut(&item).write(text)
+            }
+            _ => which!(self, "write", noun, obj, items),
+        }
+    }
+
     /// Start the Game in a command line setting where `print` macros are expected to work
+    ///
+    /// When both stdin and stdout are terminals, this is a full-screen interface
+    /// with a status line, scrollback and command history.
     #[cfg(not(target_arch = "wasm32"))]
     pub fn play(&mut self) -> Result<(), Box<dyn error::Error>> {
-        println!("{}", self.ask("look"));
+        #[cfg(feature = "tui")]
+        {
+            use std::io::IsTerminal;
+
+            if io::stdin().is_terminal() && io::stdout().is_terminal() {
+                return self.play_tui();
+            }
+        }
+
+        self.play_with(io::stdin().lock(), io::stdout())
+    }
+
+    /// Run commands from a reader, one per line, writing only their output to a writer
+    ///
+    /// Nothing is prompted for: quitting isn't confirmed and save, restore and script use their default files.
+    /// ```
+    /// # use kingslayer::Game;
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut output = Vec::new();
+    /// Game::default().play_batch("take stick\nquit\ni\n".as_bytes(), &mut output)?;
+    /// assert_eq!(String::from_utf8(output)?, "Taken.\n\n");
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn play_batch<R: BufRead, W: Write>(
+        &mut self,
+        reader: R,
+        writer: W,
+    ) -> Result<(), Box<dyn error::Error>> {
+        self.play_loop(reader, writer, false, |_| ())
+    }
+
+    /// Start the Game reading commands from any reader and writing output to any writer
+    /// ```
+    /// # use kingslayer::Game;
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut output = Vec::new();
+    /// Game::default().play_with("take stick\nquit\ny\n".as_bytes(), &mut output)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn play_with<R: BufRead, W: Write>(
+        &mut self,
+        reader: R,
+        writer: W,
+    ) -> Result<(), Box<dyn error::Error>> {
+        self.play_loop(reader, writer, true, |_| ())
+    }
+
+    // the loop of play_with and play_batch, calling before_prompt with the Game before each prompt
+    // and without prompts for batches
+    #[cfg(not(target_arch = "wasm32"))]
+    pub(crate) fn play_loop<R: BufRead, W: Write>(
+        &mut self,
+        mut reader: R,
+        mut writer: W,
+        prompts: bool,
+        mut before_prompt: impl FnMut(&Self),
+    ) -> Result<(), Box<dyn error::Error>> {
+        if prompts {
+            writeln!(writer, "{}", self.ask("look"))?;
+        }
+
+        let mut script: Option<File> = None;
 
         while !self.dead {
-            println!(
-                "{}",
-                match prompt("\n> ")?.trim() {
-                    "quit" | "q" =>
-                        if confirm_quit()? {
-                            break;
-                        } else {
-                            "Ok.".to_owned()
-                        },
-                    "restore" => self.restore("kingslayer.save"), // TODO: ask for filename
-                    "save" => self.save("kingslayer.save")?,      // TODO: ask for filename
-                    s => self.ask(s),
+            before_prompt(self);
+            let Some(input) = prompt(&mut reader, &mut writer, if prompts { "\n> " } else { "" })?
+            else {
+                break;
+            };
+
+            let output = match input.trim() {
+                "quit" | "q" => {
+                    if !prompts || confirm_quit(&mut reader, &mut writer)? {
+                        break;
+                    } else {
+                        "Ok.".to_owned()
+                    }
                 }
-            );
+                "restore" => {
+                    let filename =
+                        ask_filename(&mut reader, &mut writer, prompts, "kingslayer.save")?;
+                    self.restore(&filename)
+                }
+                "save" => {
+                    let filename =
+                        ask_filename(&mut reader, &mut writer, prompts, "kingslayer.save")?;
+                    self.save(&filename)?
+                }
+                "script" => {
+                    let filename =
+                        ask_filename(&mut reader, &mut writer, prompts, "transcript.txt")?;
+                    match File::create(&filename) {
+                        Ok(mut file) => {
+                            file.write_all(transcript::header(&self.save_to_string()?).as_bytes())?;
+                            script = Some(file);
+                            format!("Scripting to {filename}.")
+                        }
+                        Err(e) => e.to_string(),
+                    }
+                }
+                "unscript" => {
+                    if script.take().is_some() {
+                        "Scripting stopped.".to_owned()
+                    } else {
+                        "You are not scripting.".to_owned()
+                    }
+                }
+                s => {
+                    let output = self.ask(s);
+                    if let Some(file) = &mut script {
+                        file.write_all(transcript::entry(s, &output).as_bytes())?;
+                    }
+                    output
+                }
+            };
+            writeln!(writer, "{output}")?;
+            // a blank line between outputs stands in for the prompt
+            if !prompts {
+                writeln!(writer)?;
+            }
         }
 
         Ok(())
@@ -830,38 +1712,62 @@ impl Game {
         match self.item(container).container() {
             Container::Open | Container::True => {
                 self.item_mut(item).set_location(container.to_owned());
+                self.observe(&format!(
+                    "puts the {} in the {}.",
+                    self.item(item).name(),
+                    self.item(container).name()
+                ));
                 "Done.".to_owned()
             }
             Container::Closed => {
-                self.last_it = self.item(container).name().to_owned();
+                self.refer_to(container);
                 format!("The {} isn't open.", self.item(container).name())
             }
             Container::False => "You can't do that.".to_owned(),
         }
     }
 
-    // replace the noun "it" (or "them") in a Tokens with the last referenced object
-    fn replace_it(&self, tokens: Tokens) -> Tokens {
-        match (tokens.noun(), tokens.obj()) {
-            ("it", "it") => Tokens::with(
-                tokens.verb().to_owned(),
-                self.last_it.clone(),
-                tokens.prep().to_owned(),
-                self.last_it.clone(),
-            ),
-            ("it", _) => Tokens::with(
-                tokens.verb().to_owned(),
-                self.last_it.clone(),
-                tokens.prep().to_owned(),
-                tokens.obj().to_owned(),
-            ),
-            (_, "it") => Tokens::with(
+    // remember an item as what its pronoun refers to
+    fn refer_to(&mut self, item: &str) {
+        if let Some(i) = self.items.get(item) {
+            self.last_it = i.name().to_owned();
+            self.pronouns
+                .insert(i.pronoun().to_owned(), item.to_owned());
+        }
+    }
+
+    // the name of what a pronoun refers to, choosing that item over others with the same name
+    fn referent(&mut self, word: &str) -> Option<String> {
+        if let Some(item) = self
+            .pronouns
+            .get(word)
+            .filter(|item| self.items.contains_key(*item))
+            .cloned()
+        {
+            let name = self.item(&item).name().to_owned();
+            self.chosen.push(item);
+            Some(name)
+        } else if matches!(word, "it" | "them") && !self.last_it.is_empty() {
+            Some(self.last_it.clone())
+        } else {
+            None
+        }
+    }
+
+    // replace pronouns in a Tokens with the names of what they refer to
+    fn replace_it(&mut self, tokens: Tokens) -> Tokens {
+        let noun = self.referent(tokens.noun());
+        let obj = self.referent(tokens.obj());
+
+        if noun.is_none() && obj.is_none() {
+            tokens
+        } else {
+            Tokens::with(
                 tokens.verb().to_owned(),
-                tokens.noun().to_owned(),
+                noun.unwrap_or_else(|| tokens.noun().to_owned()),
                 tokens.prep().to_owned(),
-                self.last_it.clone(),
-            ),
-            _ => tokens,
+                obj.unwrap_or_else(|| tokens.obj().to_owned()),
+            )
         }
     }
 
@@ -873,14 +1779,129 @@ impl Game {
     /// ```
     #[cfg(not(target_arch = "wasm32"))]
     pub fn restore(&mut self, filename: &str) -> String {
-        match Game::load(filename) {
-            Ok(game) => {
-                self.player = game.player;
-                self.items = game.items;
+        match fs::read_to_string(filename) {
+            Ok(save) => match self.restore_from_str(&save) {
+                Ok(()) => "OK".to_owned(),
+                Err(e) => e.to_string(),
+            },
+            Err(e) => e.to_string(),
+        }
+    }
+
+    /// Restore a Game from a string made by `save_to_string`.
+    ///
+    /// Fails without changing the Game if the save was made for a different world.
+    /// ```
+    /// # use kingslayer::Game;
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut game = Game::default();
+    /// let save = game.save_to_string()?;
+    /// game.restore_from_str(&save)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn restore_from_str(&mut self, save: &str) -> Result<(), Box<dyn error::Error>> {
+        let save: Save = ron::from_str(save)?;
 
-                "OK".to_owned()
+        if save.world != self.world
+            || save
+                .items
+                .keys()
+                .chain(&save.removed)
+                .chain(save.others.keys())
+                .chain([&save.player].into_iter().filter(|id| !id.is_empty()))
+                .any(|id| !self.original.contains_key(id))
+        {
+            return Err(WorldMismatch.into());
+        }
+
+        let mut items = self.original.clone();
+        for id in &save.removed {
+            items.remove(id);
+        }
+        for (id, delta) in save.items {
+            if let Some(item) = items.get_mut(&id) {
+                item.apply(delta);
             }
-            Err(e) => e.to_string(),
+        }
+
+        self.items = items;
+        if !save.player.is_empty() {
+            self.player = save.player;
+        }
+        self.others = save.others;
+        self.join_players();
+        self.aliases = save.aliases;
+        self.inbox.clear();
+        self.aggro.clear();
+        self.dead = save.dead;
+        self.turns = save.turns;
+        self.last_command = Tokens::default();
+        self.last_it = save.last_it;
+        self.pronouns = save.pronouns;
+
+        Ok(())
+    }
+
+    /// The name of the room the player is in
+    /// ```
+    /// # use kingslayer::Game;
+    /// # let game = Game::default();
+    /// assert_eq!(game.room(), "Brig");
+    /// ```
+    pub fn room(&self) -> &str {
+        self.item(self.player_location()).name()
+    }
+
+    /// The name of the room one of the players in a shared world is in
+    /// ```
+    /// # use kingslayer::Game;
+    /// # let game = Game::default();
+    /// assert_eq!(game.room_as("PLAYER"), Some(game.room()));
+    /// ```
+    pub fn room_as(&self, player: &str) -> Option<&str> {
+        self.is_player(player)
+            .then(|| self.item(self.item(player).location()).name())
+    }
+
+    // carry out a command, remembering it for oops if part of it could not be understood
+    fn run(&mut self, tokens: &Tokens) -> String {
+        let trace = self.debug_parse.then(|| self.trace(tokens));
+        let output = match self.parse(tokens) {
+            Outcome::Active(output) => format!("{}{}", output, self.end_turn()),
+            Outcome::Idle(output) => output,
+        };
+        self.chosen.clear();
+
+        let mut words = vec![tokens.verb().to_owned()];
+        words.extend(tokens.noun().split_whitespace().map(str::to_owned));
+        let noun = 1..words.len();
+        if !tokens.prep().is_empty() {
+            words.push(tokens.prep().to_owned());
+        }
+        let obj_start = words.len();
+        words.extend(tokens.obj().split_whitespace().map(str::to_owned));
+        let obj = obj_start..words.len();
+
+        let unresolved = match tokens.action() {
+            Action::Unknown(verb) if !self.knows_verb(verb) => Some(0..1),
+            _ if !noun.is_empty() && output.starts_with(&cant_see_any(tokens.noun())) => Some(noun),
+            _ if !obj.is_empty() && output.starts_with(&cant_see_any(tokens.obj())) => Some(obj),
+            _ => None,
+        };
+        if let Some(unresolved) = unresolved {
+            // the first word that is not part of the name of anything visible
+            let names = self.visible_names().join(" ").to_lowercase();
+            let i = unresolved
+                .clone()
+                .find(|&i| !names.split_whitespace().any(|name| name == words[i]))
+                .unwrap_or(unresolved.end - 1);
+            self.oops = Some((words, i));
+        }
+
+        match trace {
+            Some(trace) => format!("{output}\n{trace}"),
+            None => output,
         }
     }
 
@@ -894,57 +1915,522 @@ impl Game {
     pub fn save(&self, filename: &str) -> Result<String, Box<dyn error::Error>> {
         Ok(match File::create(filename) {
             Ok(mut file) => {
-                file.write_all(ron::ser::to_string(self)?.as_bytes())?;
+                file.write_all(self.save_to_string()?.as_bytes())?;
                 "Saved.".to_owned()
             }
             Err(e) => e.to_string(),
         })
     }
 
+    /// Save only the changes made to the world since the Game was created.
+    /// ```
+    /// # use kingslayer::Game;
+    /// # let game = Game::default();
+    /// let save = game.save_to_string();
+    /// ```
+    pub fn save_to_string(&self) -> Result<String, ron::Error> {
+        ron::ser::to_string(&Save {
+            world: self.world.clone(),
+            player: self.player.clone(),
+            others: self.others.clone(),
+            aliases: self.aliases.clone(),
+            dead: self.dead,
+            last_it: self.last_it.clone(),
+            pronouns: self.pronouns.clone(),
+            turns: self.turns,
+            items: self
+                .items
+                .iter()
+                .filter_map(|(id, item)| {
+                    item.diff(self.original.get(id)?)
+                        .map(|delta| (id.to_owned(), delta))
+                })
+                .collect(),
+            removed: self
+                .original
+                .keys()
+                .filter(|id| !self.items.contains_key(*id))
+                .cloned()
+                .collect(),
+        })
+    }
+
+    /// Show how each command was understood after its response, as the command "debug parse" does
+    /// ```
+    /// # use kingslayer::Game;
+    /// # let mut game = Game::default();
+    /// game.set_debug_parse(true);
+    /// assert!(game.ask("take stick").contains("[action] Take(\"stick\")"));
+    /// ```
+    pub fn set_debug_parse(&mut self, on: bool) {
+        self.debug_parse = on;
+    }
+
+    /// Commands that make sense right now, each exactly as it can be given to `ask`
+    /// ```
+    /// # use kingslayer::Game;
+    /// # let mut game = Game::default();
+    /// assert!(game.suggestions().contains(&"take stick".to_owned()));
+    /// ```
+    pub fn suggestions(&self) -> Vec<String> {
+        if self.dead {
+            return Vec::new();
+        }
+
+        let mut visible = self
+            .items
+            .iter()
+            .filter(|(key, item)| {
+                **key != self.player && !self.others.contains_key(*key) && self.is_visible(item)
+            })
+            .map(|(_, item)| item)
+            .collect::<Vec<_>>();
+        visible.sort_by_key(|item| item.name().to_lowercase());
+
+        let mut suggestions = Vec::new();
+
+        for exit in visible.iter().filter(|item| self.is_visible_has_dest(item)) {
+            suggestions.push(match exit.names().iter().find(|name| name.is_direction()) {
+                Some(direction) => direction.to_owned(),
+                None => format!("go {}", exit.name().to_lowercase()),
+            });
+        }
+
+        let weapons = visible
+            .iter()
+            .filter(|item| self.in_inventory(item) && item.damage() > 0)
+            .collect::<Vec<_>>();
+
+        for item in &visible {
+            let name = item.name().to_lowercase();
+            if item.can_take() && !self.in_inventory(item) {
+                suggestions.push(format!("take {name}"));
+            }
+            match item.container() {
+                Container::Open => suggestions.push(format!("close {name}")),
+                Container::Closed => suggestions.push(format!("open {name}")),
+                _ => (),
+            }
+            if item.can_eat() {
+                suggestions.push(format!("eat {name}"));
+            }
+            if item.is_aggressive() && item.hp() > 0 {
+                for weapon in &weapons {
+                    suggestions.push(format!(
+                        "attack {name} with {}",
+                        weapon.name().to_lowercase()
+                    ));
+                }
+            }
+        }
+
+        suggestions.push("look".to_owned());
+        suggestions.push("inventory".to_owned());
+        suggestions
+    }
+
+    // the names of visible items that a noun matching nothing might be a misspelling of
+    fn similar_names(&self, noun: &str) -> Vec<String> {
+        if matches!(noun, "" | "all" | "i")
+            || noun.starts_with('"')
+            || !find_matches!(self, noun, is_visible).is_empty()
+        {
+            return Vec::new();
+        }
+
+        let mut names = self
+            .items
+            .values()
+            .filter(|item| self.is_visible(item))
+            .filter_map(|item| {
+                item.names()
+                    .iter()
+                    .map(|name| name.to_lowercase())
+                    .find(|name| {
+                        noun.split_whitespace().all(|word| {
+                            name.split_whitespace().any(|name_word| {
+                                tokens::distance(word, name_word) <= tokens::tolerance(word)
+                            })
+                        })
+                    })
+            })
+            .collect::<Vec<_>>();
+        names.sort();
+        names.dedup();
+        names
+    }
+
+    // say something out loud, maybe to someone, for anything that hears it to react to
+    fn say(&mut self, verb: &str, text: &str, to: &str) -> String {
+        let addressed = if to.is_empty() {
+            Vec::new()
+        } else {
+            match find_matches!(self, to, is_visible)[..] {
+                [] => return cant_see_any(to),
+                ref items => items.iter().map(|(loc, _)| loc.to_string()).collect(),
+            }
+        };
+
+        let text = tokens::unquote(text);
+        self.observe(&format!("{verb}s \"{text}\"."));
+        let mut res = format!("You {verb} \"{text}\".");
+
+        let location = self.player_location();
+        let mut heard = self
+            .items
+            .iter()
+            .filter_map(|(loc, item)| {
+                let near = loc == location || self.is_visible(item);
+                let trigger = item.trigger(text, near, addressed.contains(loc))?;
+                Some((loc.to_owned(), trigger.clone()))
+            })
+            .collect::<Vec<_>>();
+        heard.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+        for (loc, trigger) in heard {
+            self.item_mut(&loc).react(&trigger);
+            if trigger.opens() {
+                let door = self.item(&loc).door();
+                let door = if door.is_empty() {
+                    loc
+                } else {
+                    door.to_owned()
+                };
+                if self.item(&door).is_closed() {
+                    self.item_mut(&door).open(String::new());
+                }
+            }
+
+            if !trigger.response().is_empty() {
+                res = format!("{res}\n\n{}", trigger.response());
+            }
+            if !trigger.go().is_empty() {
+                res = format!("{res}\n\n{}", self.move_player(trigger.go().to_owned()));
+            }
+        }
+
+        res
+    }
+
+    // the words with a verb declared by the world as a synonym replaced by the verb it means
+    fn synonyms(&self, words: &[String]) -> Vec<String> {
+        let mut words = words.to_vec();
+        if let Some(verb) = words.first_mut() {
+            if let Some(synonym) = self
+                .verbs
+                .iter()
+                .find(|custom| custom.is_named(verb) && !custom.means().is_empty())
+            {
+                *verb = synonym.means().to_owned();
+            }
+        }
+        words
+    }
+
+    // make another player the one acting, keeping the state of the one before with the others
+    fn switch_player(&mut self, player: &str) {
+        if player == self.player {
+            return;
+        }
+
+        let next = self.others.remove(player).unwrap_or_default();
+        let previous = Player {
+            aliases: mem::replace(&mut self.aliases, next.aliases),
+            choices: mem::replace(&mut self.choices, next.choices),
+            dead: mem::replace(&mut self.dead, next.dead),
+            last_command: mem::replace(&mut self.last_command, next.last_command),
+            last_it: mem::replace(&mut self.last_it, next.last_it),
+            pronouns: mem::replace(&mut self.pronouns, next.pronouns),
+            oops: mem::replace(&mut self.oops, next.oops),
+        };
+        let previous_id = mem::replace(&mut self.player, player.to_owned());
+        self.others.insert(previous_id, previous);
+    }
+
     fn take(&mut self, location: &str) -> String {
-        self.items
+        let res = self
+            .items
             .get_mut(location)
             .unwrap()
             .take(&self.player)
-            .to_owned()
+            .to_owned();
+        if self.holding(self.item(location)) {
+            self.observe(&format!("takes the {}.", self.item(location).name()));
+        }
+        res
+    }
+
+    // tell another player about something they see happen
+    fn tell(&mut self, player: &str, message: String) {
+        self.inbox
+            .entry(player.to_owned())
+            .or_default()
+            .push(message);
+    }
+
+    // tell every other living player in a location, except one, about something they see happen
+    fn tell_others(&mut self, location: &str, message: &str, except: &str) {
+        for (id, player) in &self.others {
+            if !player.dead && id != except && self.items.get(id).is_some_and(|i| i.is_in(location))
+            {
+                self.inbox
+                    .entry(id.to_owned())
+                    .or_default()
+                    .push(message.to_owned());
+            }
+        }
+    }
+
+    // the words, action and visible items a command was resolved to, for finding parser bugs
+    fn trace(&self, tokens: &Tokens) -> String {
+        let mut trace = vec![
+            format!(
+                "[tokens] verb: {:?}, noun: {:?}, prep: {:?}, obj: {:?}",
+                tokens.verb(),
+                tokens.noun(),
+                tokens.prep(),
+                tokens.obj()
+            ),
+            format!("[action] {:?}", tokens.action()),
+        ];
+
+        let mut nouns = tokens.nouns();
+        if !tokens.obj().is_empty() {
+            nouns.push(tokens.obj());
+        }
+        // noun-less commands have nothing to match
+        for noun in nouns.into_iter().filter(|noun| !noun.is_empty()) {
+            let mut items = self
+                .items
+                .iter()
+                .filter(|(_, i)| self.is_visible(i) && i.names_contains(noun))
+                .map(|(loc, _)| loc)
+                .collect::<Vec<_>>();
+            items.sort();
+            let items = items
+                .into_iter()
+                .map(|loc| {
+                    if self.chosen.contains(loc) {
+                        format!("{loc} (chosen)")
+                    } else {
+                        loc.to_owned()
+                    }
+                })
+                .collect::<Vec<_>>();
+
+            trace.push(if items.is_empty() {
+                format!("[items] {noun}: none")
+            } else {
+                format!("[items] {noun}: {}", items.join(", "))
+            });
+        }
+
+        trace.join("\n")
+    }
+
+    /// The number of turns that have passed
+    /// ```
+    /// # use kingslayer::Game;
+    /// # let mut game = Game::default();
+    /// game.ask("wait and look");
+    /// assert_eq!(game.turns(), 2);
+    /// ```
+    pub fn turns(&self) -> u32 {
+        self.turns
     }
 
     fn update_last(&mut self, tokens: Tokens) {
         self.last_command = tokens;
-        if !self.last_command.noun().is_empty() && self.last_command.noun() != "all" {
-            self.last_it = self.last_command.noun().to_owned()
+
+        let nouns = self.last_command.nouns();
+        if let [noun] = nouns[..] {
+            if noun.is_empty() || noun.starts_with('"') || all_except(noun).is_some() {
+                return;
+            }
+            self.last_it = noun.to_owned();
+
+            if let [(item, _)] = find_matches!(self, noun, is_visible)[..] {
+                let item = item.to_owned();
+                self.refer_to(&item);
+            }
+        } else {
+            // several things are "them"
+            self.last_it = self.last_command.noun().to_owned();
+            self.pronouns.remove("them");
         }
     }
 
+    // the names of the verbs declared by the world and the player's aliases, for completion
+    pub(crate) fn verb_names(&self) -> Vec<String> {
+        self.verbs
+            .iter()
+            .flat_map(|verb| verb.names().iter().cloned())
+            .chain(self.aliases.keys().cloned())
+            .collect()
+    }
+
+    // the names of every item the player can see, for completion
+    pub(crate) fn visible_names(&self) -> Vec<String> {
+        self.items
+            .values()
+            .filter(|item| self.is_visible(item))
+            .flat_map(|item| item.names().iter().cloned())
+            .collect()
+    }
+
+    // ask which of the choices was meant
+    fn which(&mut self, noun: &str, choices: Vec<(String, String)>) -> String {
+        let question = format!(
+            "Which {noun}, {}?",
+            list_names(
+                &choices
+                    .iter()
+                    .map(|(_, name)| name.as_str())
+                    .collect::<Vec<_>>(),
+                "or"
+            )
+        );
+        self.choices = choices.into_iter().map(|(loc, _)| loc).collect();
+
+        question
+    }
+
     // TODO: prevent if in combat
     fn walk(&mut self, location: &str) -> String {
         let exit = self.item(location);
         let exit_dest = exit.dest().to_owned();
+        let exit_door = exit.door().to_owned();
 
-        if let Some(door) = self.items.get(exit.door()) {
+        if let Some(door) = self.items.get(&exit_door) {
             if door.is_open() {
-                self.item_mut(&self.player.clone()).set_location(exit_dest);
-                self.look()
+                self.move_player(exit_dest)
             } else {
-                self.last_it = door.name().to_owned();
-                format!("The {} is closed.", door.name())
+                let message = format!("The {} is closed.", door.name());
+                self.refer_to(&exit_door);
+                message
             }
         } else {
-            self.item_mut(&self.player.clone()).set_location(exit_dest);
-            self.look()
+            self.move_player(exit_dest)
         }
     }
 
     fn walk_fail(&mut self, location: &str) -> String {
-        self.last_it = self.item(location).name().to_owned();
+        self.refer_to(location);
         self.item(location).go_message().to_owned()
     }
+
+    // a Game starting from a world, with the fingerprint its saves are made against
+    fn with_world(mut world: World, fingerprint: String) -> Self {
+        for verb in &mut world.verbs {
+            verb.lowercase();
+        }
+        let mut game = Self {
+            player: world.player,
+            players: world.players,
+            original: world.items.clone(),
+            items: world.items,
+            dead: world.dead,
+            last_command: world.last_command,
+            last_it: world.last_it,
+            pronouns: world.pronouns,
+            turns: world.turns,
+            verbs: world.verbs,
+            world: fingerprint,
+            others: BTreeMap::new(),
+            inbox: HashMap::new(),
+            aggro: HashMap::new(),
+            aliases: BTreeMap::new(),
+            debug_parse: false,
+            oops: None,
+            choices: Vec::new(),
+            chosen: Vec::new(),
+        };
+        game.join_players();
+
+        game
+    }
+}
+
+// the names of what to leave out if the noun means everything, as in "all but the stick and sword"
+fn all_except(noun: &str) -> Option<Vec<String>> {
+    let mut words = noun.split_whitespace();
+    if words.next() != Some("all") {
+        return None;
+    }
+
+    match words.next() {
+        None => Some(Vec::new()),
+        Some("but" | "except") => Some(
+            words
+                .collect::<Vec<_>>()
+                .join(" ")
+                .split(" and ")
+                .filter(|name| !name.is_empty())
+                .map(str::to_owned)
+                .collect(),
+        ),
+        _ => None,
+    }
 }
 
 fn cant_see_any(noun: &str) -> String {
     format!("You can't see any {noun} here.")
 }
 
+fn did_you_mean(names: &[String]) -> String {
+    format!(
+        "Did you mean {}?",
+        list_names(&names.iter().map(String::as_str).collect::<Vec<_>>(), "or")
+    )
+}
+
+// each item with the first of its names that none of the others answer to, in order of that name
+fn distinguish(items: &[(&String, &Item)]) -> Vec<(String, String)> {
+    let mut choices = items
+        .iter()
+        .map(|(loc, item)| {
+            let name = item
+                .names()
+                .iter()
+                .find(|name| {
+                    let name = name.to_lowercase();
+                    items
+                        .iter()
+                        .all(|(other, i)| other == loc || !i.names_contains(&name))
+                })
+                .map_or(item.name(), String::as_str);
+
+            ((*loc).to_owned(), name.to_owned())
+        })
+        .collect::<Vec<_>>();
+    choices.sort_by(|(a_loc, a), (b_loc, b)| a.cmp(b).then(a_loc.cmp(b_loc)));
+
+    choices
+}
+
+// an error for a world that can be read but does not make sense, at the first mention of id
+fn invalid_world(world: &str, id: &str, message: String) -> ron::error::SpannedError {
+    let before = &world[..world.find(&format!("\"{id}\"")).unwrap_or(0)];
+    let position = ron::error::Position {
+        line: before.matches('\n').count() + 1,
+        col: before
+            .rsplit('\n')
+            .next()
+            .unwrap_or_default()
+            .chars()
+            .count()
+            + 1,
+    };
+
+    ron::error::SpannedError {
+        code: ron::Error::Message(message),
+        span: ron::error::Span {
+            start: position,
+            end: position,
+        },
+    }
+}
+
 fn list_names(names: &[&str], sep: &str) -> String {
     let a = if sep == "or" { "the" } else { "a" };
 
@@ -964,21 +2450,49 @@ fn list_names(names: &[&str], sep: &str) -> String {
     }
 }
 
+// print a message and read a line of input, or None if there is no more input
 #[cfg(not(target_arch = "wasm32"))]
-fn prompt(message: &str) -> io::Result<String> {
-    print!("{message}");
-    io::stdout().flush()?;
+pub(crate) fn prompt(
+    reader: &mut impl BufRead,
+    writer: &mut impl Write,
+    message: &str,
+) -> io::Result<Option<String>> {
+    write!(writer, "{message}")?;
+    writer.flush()?;
 
     let mut input = String::new();
-    io::stdin().read_line(&mut input)?;
-    Ok(input)
+    if reader.read_line(&mut input)? == 0 {
+        Ok(None)
+    } else {
+        Ok(Some(input))
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+// ask for a filename, or take the default without prompts
+fn ask_filename(
+    reader: &mut impl BufRead,
+    writer: &mut impl Write,
+    prompts: bool,
+    default: &str,
+) -> io::Result<String> {
+    if !prompts {
+        return Ok(default.to_owned());
+    }
+    let filename = prompt(reader, writer, &format!("Filename ({default}): "))?.unwrap_or_default();
+
+    Ok(match filename.trim() {
+        "" => default.to_owned(),
+        filename => filename.to_owned(),
+    })
 }
 
 #[cfg(not(target_arch = "wasm32"))]
-fn confirm_quit() -> io::Result<bool> {
-    let res = prompt("Are you sure you want to quit? (y/n): ")?
-        .trim()
-        .to_lowercase();
+pub(crate) fn confirm_quit(reader: &mut impl BufRead, writer: &mut impl Write) -> io::Result<bool> {
+    let Some(res) = prompt(reader, writer, "Are you sure you want to quit? (y/n): ")? else {
+        return Ok(true);
+    };
+    let res = res.trim().to_lowercase();
 
     Ok(res == "y" || res == "yes" || res.starts_with("y ") || res.starts_with("yes "))
 }
@@ -0,0 +1,286 @@
+use std::{
+    collections::{hash_map::RandomState, HashMap},
+    error,
+    hash::{BuildHasher, Hasher},
+    io::{self, BufRead, BufReader, Read, Write},
+    net::{TcpListener, TcpStream},
+    panic::{self, AssertUnwindSafe},
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+    thread,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::{game::Game, response::Response};
+
+const MAX_BODY: usize = 1 << 20;
+
+struct Session {
+    game: Game,
+    last_used: Instant,
+}
+
+// each session has its own lock so that a slow or failed command only holds up its own session
+type Sessions = Mutex<HashMap<String, Arc<Mutex<Session>>>>;
+
+#[derive(Deserialize)]
+struct Command {
+    input: String,
+}
+
+#[derive(Deserialize, Serialize)]
+struct SaveBody {
+    save: String,
+}
+
+#[derive(Serialize)]
+struct Suggestions {
+    suggestions: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct Created {
+    session: String,
+    #[serde(flatten)]
+    response: Response,
+}
+
+/// Serve a JSON API over HTTP where each session plays its own Game.
+///
+/// Sessions that have not been used for longer than `expiry` are removed.
+///
+/// | Request | Body | Response |
+/// | --- | --- | --- |
+/// | `POST /sessions` | a RON world, or nothing for the default world | `{"session", "output", "room", "hp", "turns", "over"}` |
+/// | `POST /sessions/{id}/commands` | `{"input": "take sword"}` | `{"output", "room", "hp", "turns", "over"}` |
+/// | `GET /sessions/{id}` | | `{"output", "room", "hp", "turns", "over"}` |
+/// | `GET /sessions/{id}/suggestions` | | `{"suggestions"}` |
+/// | `POST /sessions/{id}/save` | | `{"save"}` |
+/// | `POST /sessions/{id}/restore` | `{"save"}` | `{"output", "room", "hp", "turns", "over"}` |
+/// | `DELETE /sessions/{id}` | | |
+///
+/// Errors are returned as `{"error"}`.
+/// ```no_run
+/// # use std::{net::TcpListener, time::Duration};
+/// # fn main() -> std::io::Result<()> {
+/// kingslayer::serve_http(TcpListener::bind("127.0.0.1:8080")?, Duration::from_secs(3600))?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn serve_http(listener: TcpListener, expiry: Duration) -> io::Result<()> {
+    let sessions = Arc::new(Sessions::default());
+
+    for stream in listener.incoming() {
+        let stream = match stream {
+            Ok(stream) => stream,
+            Err(e) => {
+                eprintln!("{e}");
+                continue;
+            }
+        };
+        let sessions = Arc::clone(&sessions);
+
+        thread::spawn(move || {
+            if let Err(e) = handle(stream, &sessions, expiry) {
+                eprintln!("{e}");
+            }
+        });
+    }
+
+    Ok(())
+}
+
+fn handle(
+    stream: TcpStream,
+    sessions: &Sessions,
+    expiry: Duration,
+) -> Result<(), Box<dyn error::Error>> {
+    stream.set_read_timeout(Some(Duration::from_secs(30)))?;
+    let mut reader = BufReader::new(stream.try_clone()?);
+
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or_default().to_owned();
+    let path = parts.next().unwrap_or_default().to_owned();
+
+    let mut content_length = Some(0);
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header)? == 0 || header.trim().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.trim().eq_ignore_ascii_case("content-length") {
+                content_length = value.trim().parse().ok();
+            }
+        }
+    }
+
+    let (status, body) = match content_length {
+        None => (400, error("The Content-Length header is not a number.")),
+        Some(content_length) if content_length > MAX_BODY => {
+            (413, error("The request body is too large."))
+        }
+        Some(content_length) => {
+            let mut body = vec![0; content_length];
+            reader.read_exact(&mut body)?;
+
+            // a session that is in use has just been used
+            lock(sessions).retain(|_, session| {
+                session
+                    .try_lock()
+                    .map_or(true, |session| session.last_used.elapsed() < expiry)
+            });
+            route(&method, &path, &String::from_utf8_lossy(&body), sessions)
+        }
+    };
+
+    Ok(respond(stream, status, &body)?)
+}
+
+// a lock is never left held by a panic, so one that is poisoned can still be used
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+// run the game without letting a bug in it take down the server
+fn guard<F: FnOnce() -> (u16, String)>(f: F) -> (u16, String) {
+    panic::catch_unwind(AssertUnwindSafe(f))
+        .unwrap_or_else(|_| (500, error("The game failed to handle the request.")))
+}
+
+fn route(method: &str, path: &str, body: &str, sessions: &Sessions) -> (u16, String) {
+    let path = path.split('?').next().unwrap_or_default();
+    let segments = path
+        .split('/')
+        .filter(|s| !s.is_empty())
+        .collect::<Vec<_>>();
+
+    match (method, &segments[..]) {
+        ("OPTIONS", _) => (204, String::new()),
+        ("POST", ["sessions"]) => {
+            let game = if body.trim().is_empty() {
+                Ok(Game::default())
+            } else {
+                body.parse::<Game>()
+            };
+
+            match game {
+                Ok(mut game) => guard(|| {
+                    let output = game.ask("look");
+                    let mut sessions = lock(sessions);
+                    let id = session_id(&sessions);
+                    let created = Created {
+                        session: id.clone(),
+                        response: Response::new(&game, output),
+                    };
+                    sessions.insert(
+                        id,
+                        Arc::new(Mutex::new(Session {
+                            game,
+                            last_used: Instant::now(),
+                        })),
+                    );
+                    (201, json(&created))
+                }),
+                Err(e) => (400, error(&e.to_string())),
+            }
+        }
+        ("DELETE", ["sessions", id]) => match lock(sessions).remove(*id) {
+            Some(_) => (204, String::new()),
+            None => (404, error("There is no such session.")),
+        },
+        (_, ["sessions", id, ..]) => {
+            let Some(session) = lock(sessions).get(*id).cloned() else {
+                return (404, error("There is no such session."));
+            };
+            let mut session = lock(&session);
+            session.last_used = Instant::now();
+            let game = &mut session.game;
+
+            guard(|| match (method, &segments[2..]) {
+                ("GET", []) => (200, json(&Response::new(game, String::new()))),
+                ("POST", ["commands"]) => match serde_json::from_str::<Command>(body) {
+                    Ok(command) => {
+                        let output = game.ask(command.input);
+                        (200, json(&Response::new(game, output)))
+                    }
+                    Err(e) => (400, error(&e.to_string())),
+                },
+                ("GET", ["suggestions"]) => (
+                    200,
+                    json(&Suggestions {
+                        suggestions: game.suggestions(),
+                    }),
+                ),
+                ("POST", ["save"]) => match game.save_to_string() {
+                    Ok(save) => (200, json(&SaveBody { save })),
+                    Err(e) => (500, error(&e.to_string())),
+                },
+                ("POST", ["restore"]) => match serde_json::from_str::<SaveBody>(body) {
+                    Ok(SaveBody { save }) => match game.restore_from_str(&save) {
+                        Ok(()) => (200, json(&Response::new(game, String::new()))),
+                        Err(e) => (400, error(&e.to_string())),
+                    },
+                    Err(e) => (400, error(&e.to_string())),
+                },
+                _ => (404, error("There is no such endpoint.")),
+            })
+        }
+        _ => (404, error("There is no such endpoint.")),
+    }
+}
+
+fn respond(mut stream: TcpStream, status: u16, body: &str) -> io::Result<()> {
+    let reason = match status {
+        200 => "OK",
+        201 => "Created",
+        204 => "No Content",
+        400 => "Bad Request",
+        404 => "Not Found",
+        413 => "Payload Too Large",
+        _ => "Internal Server Error",
+    };
+
+    write!(
+        stream,
+        "HTTP/1.1 {status} {reason}\r\n\
+         Content-Type: application/json\r\n\
+         Content-Length: {}\r\n\
+         Access-Control-Allow-Origin: *\r\n\
+         Access-Control-Allow-Methods: GET, POST, DELETE, OPTIONS\r\n\
+         Access-Control-Allow-Headers: Content-Type\r\n\
+         Connection: close\r\n\r\n{body}",
+        body.len()
+    )?;
+    stream.flush()
+}
+
+fn json<T: Serialize>(value: &T) -> String {
+    serde_json::to_string(value).unwrap_or_else(|e| error(&e.to_string()))
+}
+
+fn error(message: &str) -> String {
+    json!({ "error": message }).to_string()
+}
+
+// a new random id that is hard to guess
+fn session_id(sessions: &HashMap<String, Arc<Mutex<Session>>>) -> String {
+    loop {
+        let mut hasher = RandomState::new().build_hasher();
+        hasher.write_u128(
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_nanos(),
+        );
+        let id = format!("{:016x}", hasher.finish());
+
+        if !sessions.contains_key(&id) {
+            return id;
+        }
+    }
+}
@@ -1,8 +1,10 @@
+use std::collections::BTreeMap;
+
 use serde::{Deserialize, Serialize};
 
 use crate::container::Container;
 
-#[derive(Debug, Default, Deserialize, Serialize)]
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
 enum Nature {
     #[default]
     Inanimate,
@@ -10,7 +12,7 @@ enum Nature {
     Aggressive,
 }
 
-#[derive(Debug, Default, Deserialize, Serialize)]
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
 enum Food {
     #[default]
     Not,
@@ -18,18 +20,38 @@ enum Food {
     Poisonous,
 }
 
-#[derive(Debug, Default, Deserialize, Serialize)]
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+enum Gender {
+    #[default]
+    Neuter,
+    Male,
+    Female,
+    Plural,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+enum Scope {
+    // said in the same room
+    #[default]
+    Room,
+    // said to the item
+    Addressed,
+    Anywhere,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
 enum Opacity {
     #[default]
     Opaque,
     Transparent,
 }
 
-#[derive(Debug, Default, Deserialize, Serialize)]
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
 #[serde(default)]
 pub(crate) struct Item {
     nature: Nature,
     can_take: bool,
+    can_write: bool,
     close_message: String,
     container: Container,
     covering: Vec<String>,
@@ -39,6 +61,7 @@ pub(crate) struct Item {
     details: String,
     door: String,
     food: Food,
+    gender: Gender,
     go_message: String,
     hp: i8,
     locations: Vec<String>,
@@ -47,7 +70,46 @@ pub(crate) struct Item {
     names: Vec<String>,
     opacity: Opacity,
     open_message: String,
+    // what happens when a verb is used on this item, by verb
+    responses: BTreeMap<String, String>,
     take_message: String,
+    triggers: Vec<Trigger>,
+    writing: String,
+}
+
+/// What an item does when it hears a phrase
+/// ```ron
+/// triggers: [(phrase: "parley", response: "He lowers his cutlass.", nature: Some(Passive))],
+/// ```
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(default)]
+pub(crate) struct Trigger {
+    phrase: String,
+    scope: Scope,
+    response: String,
+    // whether it opens, or opens the door of an exit
+    open: bool,
+    nature: Option<Nature>,
+    // where the player goes
+    go: String,
+}
+
+/// The parts of an Item that can change during a game
+#[derive(Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(default)]
+pub(crate) struct ItemDelta {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    container: Option<Container>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    covering: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    hp: Option<i8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    locations: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    nature: Option<Nature>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    writing: Option<String>,
 }
 
 impl Item {
@@ -59,6 +121,31 @@ impl Item {
         !matches!(self.food, Food::Not)
     }
 
+    pub(crate) const fn can_take(&self) -> bool {
+        self.can_take
+    }
+
+    pub(crate) fn apply(&mut self, delta: ItemDelta) {
+        if let Some(container) = delta.container {
+            self.container = container;
+        }
+        if let Some(covering) = delta.covering {
+            self.covering = covering;
+        }
+        if let Some(hp) = delta.hp {
+            self.hp = hp;
+        }
+        if let Some(locations) = delta.locations {
+            self.locations = locations;
+        }
+        if let Some(nature) = delta.nature {
+            self.nature = nature;
+        }
+        if let Some(writing) = delta.writing {
+            self.writing = writing;
+        }
+    }
+
     pub(crate) fn close(&mut self) -> String {
         match self.container {
             Container::Open => {
@@ -96,6 +183,24 @@ impl Item {
         &self.details
     }
 
+    // the changes made to this item since it was the original
+    pub(crate) fn diff(&self, original: &Self) -> Option<ItemDelta> {
+        let delta = ItemDelta {
+            container: (self.container != original.container).then(|| self.container.clone()),
+            covering: (self.covering != original.covering).then(|| self.covering.clone()),
+            hp: (self.hp != original.hp).then_some(self.hp),
+            locations: (self.locations != original.locations).then(|| self.locations.clone()),
+            nature: (self.nature != original.nature).then(|| self.nature.clone()),
+            writing: (self.writing != original.writing).then(|| self.writing.clone()),
+        };
+
+        if delta == ItemDelta::default() {
+            None
+        } else {
+            Some(delta)
+        }
+    }
+
     pub(crate) fn door(&self) -> &str {
         &self.door
     }
@@ -177,6 +282,10 @@ impl Item {
         }
     }
 
+    pub(crate) fn names(&self) -> &[String] {
+        &self.names
+    }
+
     pub(crate) fn names_contains(&self, search: &str) -> bool {
         self.names.iter().any(|name| {
             let name = name.to_lowercase();
@@ -207,6 +316,20 @@ impl Item {
         }
     }
 
+    // the word used to refer to this item again
+    pub(crate) const fn pronoun(&self) -> &str {
+        match self.gender {
+            Gender::Neuter => "it",
+            Gender::Male => "him",
+            Gender::Female => "her",
+            Gender::Plural => "them",
+        }
+    }
+
+    pub(crate) fn response(&self, verb: &str) -> Option<&str> {
+        self.responses.get(verb).map(String::as_str)
+    }
+
     pub(crate) fn set_location(&mut self, location: String) {
         self.locations = vec![location];
     }
@@ -222,7 +345,68 @@ impl Item {
         }
     }
 
+    // the items the triggers send the player to, which must be in the world
+    pub(crate) fn targets(&self) -> impl Iterator<Item = &str> {
+        self.triggers
+            .iter()
+            .map(Trigger::go)
+            .filter(|id| !id.is_empty())
+    }
+
+    // what this item does when it hears the text, if it is near or spoken to
+    pub(crate) fn trigger(&self, text: &str, near: bool, addressed: bool) -> Option<&Trigger> {
+        self.triggers.iter().find(|trigger| {
+            words(&trigger.phrase) == words(text)
+                && match trigger.scope {
+                    Scope::Room => near || addressed,
+                    Scope::Addressed => addressed,
+                    Scope::Anywhere => true,
+                }
+        })
+    }
+
+    pub(crate) fn react(&mut self, trigger: &Trigger) {
+        if let Some(nature) = &trigger.nature {
+            self.nature = nature.clone();
+        }
+    }
+
     pub(crate) fn try_take(&self) -> bool {
         self.can_take || !self.take_message.is_empty()
     }
+
+    pub(crate) fn write(&mut self, text: &str) -> String {
+        if self.can_write {
+            self.writing = text.to_owned();
+            "Written.".to_owned()
+        } else {
+            format!("You can't write on the {}.", self.name())
+        }
+    }
+
+    pub(crate) fn writing(&self) -> &str {
+        &self.writing
+    }
+}
+
+impl Trigger {
+    pub(crate) fn go(&self) -> &str {
+        &self.go
+    }
+
+    pub(crate) const fn opens(&self) -> bool {
+        self.open
+    }
+
+    pub(crate) fn response(&self) -> &str {
+        &self.response
+    }
+}
+
+// the words of a phrase in lowercase without punctuation
+fn words(s: &str) -> Vec<String> {
+    s.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(str::to_lowercase)
+        .collect()
 }
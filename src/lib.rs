@@ -5,8 +5,27 @@
 mod action;
 mod container;
 mod direction;
+#[cfg(feature = "ffi")]
+pub mod ffi;
 mod game;
+#[cfg(feature = "http")]
+mod http;
 mod item;
+mod player;
+mod protocol;
+mod response;
+mod save;
+#[cfg(all(feature = "tui", not(target_arch = "wasm32")))]
+mod screen;
+#[cfg(not(target_arch = "wasm32"))]
+mod server;
 mod tokens;
+mod transcript;
+#[cfg(all(feature = "tui", not(target_arch = "wasm32")))]
+mod tui;
+mod verb;
 
 pub use game::Game;
+#[cfg(feature = "http")]
+pub use http::serve_http;
+pub use transcript::{test_walkthrough, Mismatch, Transcript, WalkthroughError};
@@ -1,4 +1,10 @@
-use std::{env, error, fs, process::ExitCode};
+use std::{
+    env, error,
+    fs::{self, File},
+    io::{self, BufReader},
+    net::TcpListener,
+    process::ExitCode,
+};
 
 use kingslayer::*;
 
@@ -13,13 +19,111 @@ fn main() -> ExitCode {
 }
 
 fn run() -> Result<ExitCode, Box<dyn error::Error>> {
-    let mut game: Game = if let Some(filename) = env::args().nth(1) {
-        fs::read_to_string(filename)?.parse()?
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    match args.iter().map(String::as_str).collect::<Vec<_>>()[..] {
+        ["--batch"] => batch(None, None),
+        ["--batch", commands] => batch(None, Some(commands)),
+        [world, "--batch"] => batch(Some(world), None),
+        [world, "--batch", commands] => batch(Some(world), Some(commands)),
+        ["--protocol", "json"] => json(None),
+        [world, "--protocol", "json"] => json(Some(world)),
+        ["replay", transcript] => test(None, transcript),
+        ["replay", transcript, world] | ["test", world, transcript] => {
+            test(Some(world), transcript)
+        }
+        #[cfg(feature = "http")]
+        ["http", "--port", port] => http(port),
+        ["serve", "--port", port] => serve(None, port),
+        ["serve", "--port", port, world] => serve(Some(world), port),
+        [world] => play(Some(world)),
+        [] => play(None),
+        _ => Err(format!(
+            "{}{}",
+            concat!(
+                "usage: kingslayer [world.ron]\n",
+                "       kingslayer [world.ron] --batch [commands.txt]\n",
+                "       kingslayer [world.ron] --protocol json\n",
+                "       kingslayer replay transcript.txt [world.ron]\n",
+                "       kingslayer test world.ron walkthrough.txt\n",
+                "       kingslayer serve --port N [world.ron]"
+            ),
+            if cfg!(feature = "http") {
+                "\n       kingslayer http --port N"
+            } else {
+                ""
+            }
+        )
+        .into()),
+    }
+}
+
+fn world(filename: Option<&str>) -> Result<String, Box<dyn error::Error>> {
+    Ok(if let Some(filename) = filename {
+        fs::read_to_string(filename)?
+    } else {
+        include_str!("world.ron").to_owned()
+    })
+}
+
+fn play(filename: Option<&str>) -> Result<ExitCode, Box<dyn error::Error>> {
+    world(filename)?.parse::<Game>()?.play()?;
+
+    Ok(ExitCode::SUCCESS)
+}
+
+// run one command per line without prompting
+// the exit status is 2 if the player dies, 1 on an error and otherwise 0, as there is no winning
+fn batch(
+    filename: Option<&str>,
+    commands: Option<&str>,
+) -> Result<ExitCode, Box<dyn error::Error>> {
+    let mut game: Game = world(filename)?.parse()?;
+
+    if let Some(commands) = commands {
+        game.play_batch(BufReader::new(File::open(commands)?), io::stdout().lock())?;
+    } else {
+        game.play_batch(io::stdin().lock(), io::stdout().lock())?;
+    }
+
+    Ok(if game.is_dead() {
+        ExitCode::from(2)
     } else {
-        include_str!("world.ron").parse()?
-    };
+        ExitCode::SUCCESS
+    })
+}
+
+#[cfg(feature = "http")]
+fn http(port: &str) -> Result<ExitCode, Box<dyn error::Error>> {
+    let listener = TcpListener::bind(("127.0.0.1", port.parse()?))?;
+
+    eprintln!("Listening on http://{}", listener.local_addr()?);
+    serve_http(listener, std::time::Duration::from_secs(60 * 60))?;
+
+    Ok(ExitCode::SUCCESS)
+}
+
+// answer line-delimited JSON requests on stdin for another frontend
+fn json(filename: Option<&str>) -> Result<ExitCode, Box<dyn error::Error>> {
+    world(filename)?
+        .parse::<Game>()?
+        .play_json(io::stdin().lock(), io::stdout().lock())?;
+
+    Ok(ExitCode::SUCCESS)
+}
+
+fn serve(filename: Option<&str>, port: &str) -> Result<ExitCode, Box<dyn error::Error>> {
+    let game: Game = world(filename)?.parse()?;
+    let listener = TcpListener::bind(("0.0.0.0", port.parse()?))?;
+
+    eprintln!("Listening on {}", listener.local_addr()?);
+    game.serve(listener)?;
+
+    Ok(ExitCode::SUCCESS)
+}
 
-    game.play()?;
+fn test(filename: Option<&str>, walkthrough: &str) -> Result<ExitCode, Box<dyn error::Error>> {
+    test_walkthrough(&world(filename)?, &fs::read_to_string(walkthrough)?)?;
 
     Ok(ExitCode::SUCCESS)
 }
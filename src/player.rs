@@ -0,0 +1,21 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::tokens::Tokens;
+
+/// The state of a player in a shared world while another player is acting
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub(crate) struct Player {
+    pub(crate) aliases: BTreeMap<String, String>,
+    #[serde(skip)]
+    pub(crate) choices: Vec<String>,
+    pub(crate) dead: bool,
+    #[serde(skip)]
+    pub(crate) last_command: Tokens,
+    pub(crate) last_it: String,
+    pub(crate) pronouns: BTreeMap<String, String>,
+    #[serde(skip)]
+    pub(crate) oops: Option<(Vec<String>, usize)>,
+}
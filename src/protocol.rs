@@ -0,0 +1,126 @@
+use std::{
+    error,
+    io::{BufRead, Write},
+};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{game::Game, response::Response};
+
+#[derive(Deserialize)]
+struct Envelope {
+    #[serde(default)]
+    id: Option<Value>,
+    #[serde(flatten)]
+    request: Request,
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+enum Request {
+    Ask { input: String },
+    Restore { save: String },
+    Save,
+    State,
+    Suggestions,
+}
+
+#[derive(Serialize)]
+struct Reply {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    id: Option<Value>,
+    #[serde(flatten)]
+    response: Response,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    save: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    suggestions: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+impl Game {
+    /// Play the Game over a line-delimited JSON protocol, for use by other frontends.
+    ///
+    /// Each line read is a request and each line written is its response.
+    /// Every response has the `output` of the request along with the `room`, `hp`, `turns`
+    /// and whether the game is `over`, and echoes back the request's `id` if it had one.
+    ///
+    /// | Request | Response |
+    /// | --- | --- |
+    /// | `{"type": "ask", "input": "take sword"}` | |
+    /// | `{"type": "save"}` | `{"save"}` |
+    /// | `{"type": "restore", "save": "..."}` | |
+    /// | `{"type": "state"}` | |
+    /// | `{"type": "suggestions"}` | `{"suggestions"}` |
+    ///
+    /// A request that fails has an `error` in its response.
+    /// ```
+    /// # use kingslayer::Game;
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut output = Vec::new();
+    /// Game::default().play_json(&b"{\"type\": \"ask\", \"input\": \"look\"}\n"[..], &mut output)?;
+    /// assert!(String::from_utf8(output)?.starts_with("{\"output\":\"Brig\\n"));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn play_json<R: BufRead, W: Write>(
+        &mut self,
+        reader: R,
+        mut writer: W,
+    ) -> Result<(), Box<dyn error::Error>> {
+        for line in reader.lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let reply = match serde_json::from_str::<Envelope>(&line) {
+                Ok(Envelope { id, request }) => self.reply(id, request),
+                Err(e) => Reply {
+                    id: None,
+                    response: Response::new(self, String::new()),
+                    save: None,
+                    suggestions: None,
+                    error: Some(e.to_string()),
+                },
+            };
+
+            serde_json::to_writer(&mut writer, &reply)?;
+            writeln!(writer)?;
+            writer.flush()?;
+        }
+
+        Ok(())
+    }
+
+    fn reply(&mut self, id: Option<Value>, request: Request) -> Reply {
+        let mut reply = Reply {
+            id,
+            response: Response::new(self, String::new()),
+            save: None,
+            suggestions: None,
+            error: None,
+        };
+
+        match request {
+            Request::Ask { input } => {
+                let output = self.ask(input);
+                reply.response = Response::new(self, output);
+            }
+            Request::Restore { save } => match self.restore_from_str(&save) {
+                Ok(()) => reply.response = Response::new(self, "OK".to_owned()),
+                Err(e) => reply.error = Some(e.to_string()),
+            },
+            Request::Save => match self.save_to_string() {
+                Ok(save) => reply.save = Some(save),
+                Err(e) => reply.error = Some(e.to_string()),
+            },
+            Request::State => (),
+            Request::Suggestions => reply.suggestions = Some(self.suggestions()),
+        }
+
+        reply
+    }
+}
@@ -0,0 +1,25 @@
+use serde::Serialize;
+
+use crate::game::Game;
+
+/// The output of a command along with the state of the Game after it
+#[derive(Debug, Serialize)]
+pub(crate) struct Response {
+    output: String,
+    room: String,
+    hp: i8,
+    turns: u32,
+    over: bool,
+}
+
+impl Response {
+    pub(crate) fn new(game: &Game, output: String) -> Self {
+        Self {
+            output,
+            room: game.room().to_owned(),
+            hp: game.hp(),
+            turns: game.turns(),
+            over: game.is_dead(),
+        }
+    }
+}
@@ -0,0 +1,45 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    error, fmt,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{item::ItemDelta, player::Player};
+
+/// The changes made to a world during a Game
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub(crate) struct Save {
+    pub(crate) world: String,
+    pub(crate) player: String,
+    pub(crate) others: BTreeMap<String, Player>,
+    pub(crate) aliases: BTreeMap<String, String>,
+    pub(crate) dead: bool,
+    pub(crate) last_it: String,
+    pub(crate) pronouns: BTreeMap<String, String>,
+    pub(crate) turns: u32,
+    pub(crate) items: BTreeMap<String, ItemDelta>,
+    pub(crate) removed: BTreeSet<String>,
+}
+
+/// The save was made for a different world than the one it is being restored into
+#[derive(Debug)]
+pub(crate) struct WorldMismatch;
+
+impl fmt::Display for WorldMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "That save was made for a different world.")
+    }
+}
+
+impl error::Error for WorldMismatch {}
+
+// FNV-1a hash of the world source, stable across builds and platforms
+pub(crate) fn fingerprint(world: &str) -> String {
+    let hash = world.bytes().fold(0xcbf2_9ce4_8422_2325_u64, |hash, b| {
+        (hash ^ u64::from(b)).wrapping_mul(0x0100_0000_01b3)
+    });
+
+    format!("{hash:016x}")
+}
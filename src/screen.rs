@@ -0,0 +1,342 @@
+use std::mem;
+
+use crate::tokens;
+
+const MAX_HISTORY: usize = 500;
+const MAX_SCROLLBACK: usize = 5000;
+
+/// What the full-screen terminal shows and the line being edited, apart from the terminal itself
+#[derive(Default)]
+pub(crate) struct Screen {
+    pub(crate) status: (String, String),
+    // the names of verbs and visible items, for completion
+    pub(crate) verbs: Vec<String>,
+    pub(crate) names: Vec<String>,
+    scrollback: Vec<String>,
+    pending: String,
+    // lines scrolled up from the bottom of the output pane
+    scroll: usize,
+    input: Vec<char>,
+    cursor: usize,
+    history: Vec<String>,
+    // the history entry being edited, or history.len() for a new line
+    history_pos: usize,
+    // a submitted line not yet read
+    pub(crate) line: Vec<u8>,
+    pub(crate) eof: bool,
+}
+
+/// A change to the input line or the view, made by a key
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum Edit {
+    Insert(char),
+    Backspace,
+    Delete,
+    Left,
+    Right,
+    Home,
+    End,
+    // delete everything before the cursor
+    Kill,
+    Up,
+    Down,
+    PageUp,
+    PageDown,
+    Complete,
+    Submit,
+    // end the input, but only on an empty line
+    EndOfInput,
+    Interrupt,
+}
+
+impl Screen {
+    pub(crate) fn edit(&mut self, edit: Edit) {
+        match edit {
+            Edit::Submit => self.submit(),
+            Edit::Complete => self.complete(),
+            Edit::Interrupt => self.eof = true,
+            Edit::EndOfInput if self.input.is_empty() => self.eof = true,
+            Edit::Home => self.cursor = 0,
+            Edit::End => self.cursor = self.input.len(),
+            Edit::Kill => {
+                self.input.drain(..self.cursor);
+                self.cursor = 0;
+            }
+            Edit::Insert(c) => {
+                self.input.insert(self.cursor, c);
+                self.cursor += 1;
+            }
+            Edit::Backspace if self.cursor > 0 => {
+                self.cursor -= 1;
+                self.input.remove(self.cursor);
+            }
+            Edit::Delete if self.cursor < self.input.len() => {
+                self.input.remove(self.cursor);
+            }
+            Edit::Left => self.cursor = self.cursor.saturating_sub(1),
+            Edit::Right => self.cursor = (self.cursor + 1).min(self.input.len()),
+            Edit::Up if self.history_pos > 0 => self.recall(self.history_pos - 1),
+            Edit::Down if self.history_pos < self.history.len() => {
+                self.recall(self.history_pos + 1)
+            }
+            Edit::PageUp => self.scroll += 10,
+            Edit::PageDown => self.scroll = self.scroll.saturating_sub(10),
+            _ => (),
+        }
+    }
+
+    // the input line scrolled horizontally to keep the cursor visible, and the cursor's column
+    pub(crate) fn input_line(&self, width: usize) -> (String, usize) {
+        let prompt = self.pending.chars().collect::<Vec<_>>();
+        let line = [&prompt[..], &self.input[..]].concat();
+        let cursor = prompt.len() + self.cursor;
+        let start = (cursor + 1).saturating_sub(width);
+
+        (line[start..].iter().take(width).collect(), cursor - start)
+    }
+
+    // the rows of output that fit in the pane, wrapped to its width, scrolled no further than the top
+    pub(crate) fn output_rows(&mut self, width: usize, rows: usize) -> Vec<String> {
+        let lines = self
+            .scrollback
+            .iter()
+            .flat_map(|line| wrap(line, width))
+            .collect::<Vec<_>>();
+        self.scroll = self.scroll.min(lines.len().saturating_sub(rows));
+        let end = lines.len() - self.scroll;
+
+        lines[end.saturating_sub(rows)..end].to_vec()
+    }
+
+    // the status line with the room on the left and the stats on the right
+    pub(crate) fn status_line(&self, width: usize) -> String {
+        let (left, right) = &self.status;
+        let gap = width.saturating_sub(left.chars().count() + right.chars().count() + 2);
+
+        format!(" {left}{}{right} ", " ".repeat(gap))
+            .chars()
+            .take(width)
+            .collect()
+    }
+
+    // add output, where text after the last newline is the prompt of the input line
+    pub(crate) fn write(&mut self, text: &str) {
+        for c in text.chars() {
+            if c == '\n' {
+                let line = mem::take(&mut self.pending);
+                self.push_line(line);
+            } else {
+                self.pending.push(c);
+            }
+        }
+    }
+
+    fn push_line(&mut self, line: String) {
+        self.scrollback.push(line);
+        if self.scrollback.len() > MAX_SCROLLBACK {
+            self.scrollback.remove(0);
+        }
+    }
+
+    // finish the input if there is one way to, otherwise as far as every way agrees and list them
+    fn complete(&mut self) {
+        let input = self.input.iter().collect::<String>();
+        let candidates = tokens::complete(&input, &self.verbs, &self.names);
+
+        let completed = match &candidates[..] {
+            [] => return,
+            [candidate] => format!("{candidate} "),
+            [first, rest @ ..] => {
+                let common = rest.iter().fold(first.clone(), |common, candidate| {
+                    common
+                        .chars()
+                        .zip(candidate.chars())
+                        .take_while(|(a, b)| a == b)
+                        .map(|(a, _)| a)
+                        .collect()
+                });
+
+                if common.chars().count() <= input.chars().count() {
+                    self.push_line(candidates.join("  "));
+                    self.scroll = 0;
+                    return;
+                }
+                common
+            }
+        };
+
+        self.input = completed.chars().collect();
+        self.cursor = self.input.len();
+    }
+
+    // replace the input with an entry of the history, or an empty line past the end of it
+    fn recall(&mut self, pos: usize) {
+        self.history_pos = pos;
+        self.input = self
+            .history
+            .get(pos)
+            .map_or_else(Vec::new, |line| line.chars().collect());
+        self.cursor = self.input.len();
+    }
+
+    fn submit(&mut self) {
+        let input = mem::take(&mut self.input).into_iter().collect::<String>();
+        self.cursor = 0;
+        self.scroll = 0;
+
+        let echo = mem::take(&mut self.pending) + &input;
+        self.push_line(echo);
+
+        if !input.trim().is_empty() && self.history.last() != Some(&input) {
+            self.history.push(input.clone());
+            if self.history.len() > MAX_HISTORY {
+                self.history.remove(0);
+            }
+        }
+        self.history_pos = self.history.len();
+
+        self.line = (input + "\n").into_bytes();
+    }
+}
+
+// split a line into rows no wider than width, breaking between words where possible
+fn wrap(line: &str, width: usize) -> Vec<String> {
+    let mut rows = Vec::new();
+    let mut row = String::new();
+    let mut len = 0;
+
+    for word in line.split(' ') {
+        let word_len = word.chars().count();
+        if len > 0 && len + 1 + word_len > width {
+            rows.push(mem::take(&mut row));
+            len = 0;
+        } else if len > 0 {
+            row.push(' ');
+            len += 1;
+        }
+
+        // words longer than a row are split
+        for c in word.chars() {
+            if len == width {
+                rows.push(mem::take(&mut row));
+                len = 0;
+            }
+            row.push(c);
+            len += 1;
+        }
+    }
+    rows.push(row);
+
+    rows
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn typed(screen: &mut Screen, text: &str) {
+        for c in text.chars() {
+            screen.edit(Edit::Insert(c));
+        }
+    }
+
+    fn input(screen: &Screen) -> String {
+        screen.input.iter().collect()
+    }
+
+    #[test]
+    fn wrapping() {
+        assert_eq!(wrap("", 10), [""]);
+        assert_eq!(wrap("You are in a room.", 10), ["You are in", "a room."]);
+        assert_eq!(wrap("abcdefghijkl", 5), ["abcde", "fghij", "kl"]);
+        assert_eq!(wrap("a bcdefgh", 4), ["a", "bcde", "fgh"]);
+
+        let mut screen = Screen::default();
+        screen.write("one\ntwo three four\n> ");
+        assert_eq!(screen.output_rows(9, 2), ["two three", "four"]);
+        assert_eq!(screen.output_rows(9, 5), ["one", "two three", "four"]);
+
+        // scrolling stops at the top, so one page down is back at the bottom
+        screen.edit(Edit::PageUp);
+        assert_eq!(screen.output_rows(9, 2), ["one", "two three"]);
+        screen.edit(Edit::PageUp);
+        assert_eq!(screen.output_rows(9, 2), ["one", "two three"]);
+        screen.edit(Edit::PageDown);
+        assert_eq!(screen.output_rows(9, 2), ["two three", "four"]);
+    }
+
+    #[test]
+    fn editing() {
+        let mut screen = Screen::default();
+        screen.write("> ");
+        typed(&mut screen, "tke");
+        screen.edit(Edit::Home);
+        screen.edit(Edit::Right);
+        screen.edit(Edit::Insert('a'));
+        assert_eq!(input(&screen), "take");
+        assert_eq!(screen.input_line(80), ("> take".to_owned(), 4));
+
+        // the input scrolls to keep the cursor in view
+        assert_eq!(screen.input_line(4), (" tak".to_owned(), 3));
+        screen.edit(Edit::End);
+        assert_eq!(screen.input_line(4), ("ake".to_owned(), 3));
+
+        screen.edit(Edit::Left);
+        screen.edit(Edit::Kill);
+        screen.edit(Edit::Delete);
+        assert_eq!(input(&screen), "");
+        screen.edit(Edit::Backspace);
+
+        screen.edit(Edit::EndOfInput);
+        assert!(screen.eof);
+    }
+
+    #[test]
+    fn history() {
+        let mut screen = Screen::default();
+        for line in ["look", "look", "", "take stick"] {
+            screen.write("> ");
+            typed(&mut screen, line);
+            screen.edit(Edit::Submit);
+            assert_eq!(screen.line, format!("{line}\n").into_bytes());
+        }
+        assert_eq!(screen.output_rows(80, 2), ["> ", "> take stick"]);
+
+        // repeated and empty lines are not remembered
+        screen.edit(Edit::Up);
+        assert_eq!(input(&screen), "take stick");
+        screen.edit(Edit::Up);
+        assert_eq!(input(&screen), "look");
+        screen.edit(Edit::Up);
+        assert_eq!(input(&screen), "look");
+        screen.edit(Edit::Down);
+        screen.edit(Edit::Down);
+        assert_eq!(input(&screen), "");
+        screen.edit(Edit::Down);
+        assert_eq!(input(&screen), "");
+    }
+
+    #[test]
+    fn completion() {
+        let mut screen = Screen {
+            names: vec!["stick".to_owned(), "stone".to_owned()],
+            ..Screen::default()
+        };
+
+        typed(&mut screen, "exa");
+        screen.edit(Edit::Complete);
+        assert_eq!(input(&screen), "examine ");
+
+        // as far as the candidates agree, then a list of them
+        typed(&mut screen, "s");
+        screen.edit(Edit::Complete);
+        assert_eq!(input(&screen), "examine st");
+        screen.edit(Edit::Complete);
+        assert_eq!(input(&screen), "examine st");
+        assert_eq!(screen.output_rows(80, 1), ["examine stick  examine stone"]);
+
+        typed(&mut screen, "i");
+        screen.edit(Edit::Complete);
+        assert_eq!(input(&screen), "examine stick ");
+    }
+}
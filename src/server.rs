@@ -0,0 +1,232 @@
+use std::{
+    collections::BTreeMap,
+    error,
+    io::{self, BufReader, Read, Write},
+    mem,
+    net::{TcpListener, TcpStream},
+    panic::{self, AssertUnwindSafe},
+    thread,
+};
+
+use crate::game::{confirm_quit, prompt, Game};
+
+const MAX_LINE: usize = 1024;
+
+// telnet commands
+const IAC: u8 = 255;
+const SB: u8 = 250;
+const SE: u8 = 240;
+const WILL: u8 = 251;
+const DONT: u8 = 254;
+
+/// Reads lines from a telnet or raw TCP client, removing telnet commands and applying line editing
+struct Telnet<R> {
+    inner: R,
+    line: Vec<u8>,
+    pos: usize,
+    after_cr: bool,
+}
+
+impl<R: Read> Telnet<R> {
+    fn new(inner: R) -> Self {
+        Self {
+            inner,
+            line: Vec::new(),
+            pos: 0,
+            after_cr: false,
+        }
+    }
+
+    fn next_byte(&mut self) -> io::Result<Option<u8>> {
+        let mut byte = [0];
+        match self.inner.read(&mut byte)? {
+            0 => Ok(None),
+            _ => Ok(Some(byte[0])),
+        }
+    }
+
+    // read a full line into self.line, or leave it empty if the client disconnected
+    fn read_line(&mut self) -> io::Result<()> {
+        self.line.clear();
+        self.pos = 0;
+
+        while let Some(byte) = self.next_byte()? {
+            let after_cr = mem::take(&mut self.after_cr);
+
+            match byte {
+                IAC => match self.next_byte()? {
+                    Some(WILL..=DONT) => {
+                        self.next_byte()?;
+                    }
+                    Some(SB) => {
+                        while let Some(byte) = self.next_byte()? {
+                            if byte == IAC && self.next_byte()? == Some(SE) {
+                                break;
+                            }
+                        }
+                    }
+                    _ => (),
+                },
+                b'\n' | b'\0' if after_cr => (),
+                b'\r' | b'\n' => {
+                    self.after_cr = byte == b'\r';
+                    self.line.push(b'\n');
+                    return Ok(());
+                }
+                // backspace and delete
+                8 | 127 => {
+                    self.line.pop();
+                }
+                // ctrl-d on an empty line disconnects
+                4 if self.line.is_empty() => return Ok(()),
+                byte if byte < b' ' => (),
+                byte => {
+                    if self.line.len() < MAX_LINE {
+                        self.line.push(byte);
+                    }
+                }
+            }
+        }
+
+        self.line.clear();
+        Ok(())
+    }
+}
+
+impl<R: Read> Read for Telnet<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.pos == self.line.len() {
+            self.read_line()?;
+        }
+
+        let n = buf.len().min(self.line.len() - self.pos);
+        buf[..n].copy_from_slice(&self.line[self.pos..self.pos + n]);
+        self.pos += n;
+        Ok(n)
+    }
+}
+
+/// Writes "\r\n" line endings as expected by telnet clients
+struct Crlf<W>(W);
+
+impl<W: Write> Write for Crlf<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        for line in buf.split_inclusive(|&b| b == b'\n') {
+            if let Some(line) = line.strip_suffix(b"\n") {
+                self.0.write_all(line)?;
+                self.0.write_all(b"\r\n")?;
+            } else {
+                self.0.write_all(line)?;
+            }
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.0.flush()
+    }
+}
+
+impl Game {
+    /// Accept telnet or raw TCP connections, each playing its own copy of the Game.
+    ///
+    /// Players can `save` and `restore` named slots, which last until they disconnect. Each
+    /// connection has its own thread, and a command that fails is reported only to its player.
+    /// ```no_run
+    /// # use kingslayer::Game;
+    /// # use std::net::TcpListener;
+    /// # fn main() -> std::io::Result<()> {
+    /// Game::default().serve(TcpListener::bind("0.0.0.0:2323")?)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn serve(&self, listener: TcpListener) -> io::Result<()> {
+        for stream in listener.incoming() {
+            let stream = match stream {
+                Ok(stream) => stream,
+                Err(e) => {
+                    eprintln!("{e}");
+                    continue;
+                }
+            };
+            let game = self.clone();
+
+            thread::spawn(move || {
+                let peer = stream
+                    .peer_addr()
+                    .map_or_else(|_| "unknown".to_owned(), |addr| addr.to_string());
+
+                eprintln!("{peer} connected");
+                if let Err(e) = session(game, stream) {
+                    eprintln!("{peer}: {e}");
+                }
+                eprintln!("{peer} disconnected");
+            });
+        }
+
+        Ok(())
+    }
+}
+
+fn session(mut game: Game, stream: TcpStream) -> Result<(), Box<dyn error::Error>> {
+    let mut reader = BufReader::new(Telnet::new(BufReader::new(stream.try_clone()?)));
+    let mut writer = Crlf(stream);
+    let mut slots = BTreeMap::new();
+
+    writeln!(writer, "{}", game.ask("look"))?;
+
+    while !game.is_dead() {
+        let Some(input) = prompt(&mut reader, &mut writer, "\n> ")? else {
+            break;
+        };
+
+        let output = match input.split_whitespace().collect::<Vec<_>>()[..] {
+            ["quit" | "q"] => {
+                if confirm_quit(&mut reader, &mut writer)? {
+                    break;
+                } else {
+                    "Ok.".to_owned()
+                }
+            }
+            ["save"] => save(&game, &mut slots, "1")?,
+            ["save", slot] => save(&game, &mut slots, slot)?,
+            ["restore"] => restore(&mut game, &slots, "1"),
+            ["restore", slot] => restore(&mut game, &slots, slot),
+            ["saves"] => {
+                if slots.is_empty() {
+                    "You have not saved.".to_owned()
+                } else {
+                    format!(
+                        "Saved slots: {}",
+                        slots.keys().cloned().collect::<Vec<_>>().join(", ")
+                    )
+                }
+            }
+            // a bug in the game shouldn't cost the player their session
+            _ => panic::catch_unwind(AssertUnwindSafe(|| game.ask(input.trim())))
+                .unwrap_or_else(|_| "Something went wrong with that command.".to_owned()),
+        };
+        writeln!(writer, "{output}")?;
+    }
+
+    Ok(())
+}
+
+fn save(
+    game: &Game,
+    slots: &mut BTreeMap<String, String>,
+    slot: &str,
+) -> Result<String, Box<dyn error::Error>> {
+    slots.insert(slot.to_owned(), game.save_to_string()?);
+    Ok(format!("Saved to slot {slot}."))
+}
+
+fn restore(game: &mut Game, slots: &BTreeMap<String, String>, slot: &str) -> String {
+    match slots.get(slot) {
+        Some(save) => match game.restore_from_str(save) {
+            Ok(()) => "OK".to_owned(),
+            Err(e) => e.to_string(),
+        },
+        None => format!("There is no save in slot {slot}."),
+    }
+}
@@ -1,6 +1,11 @@
+use std::mem;
+
 use serde::{Deserialize, Serialize};
 
-use crate::{action::Action, direction::Direction};
+use crate::{
+    action::Action,
+    direction::{Direction, DIRECTIONS},
+};
 
 const USELESS_WORDS: [&str; 17] = [
     "a", "am", "an", "across", "around", "at", "for", "is", "of", "my", "no", "that", "the",
@@ -8,6 +13,296 @@ const USELESS_WORDS: [&str; 17] = [
 ];
 const PREPOSITIONS: [&str; 6] = ["in", "from", "on", "out", "under", "with"];
 
+macro_rules! do_or_ask {
+    ($action:ident, $noun:ident, $verb:ident) => {
+        if $noun.is_empty() {
+            Action::what_do($verb)
+        } else {
+            Action::$action($noun.to_owned())
+        }
+    };
+}
+
+// how Tokens::parse understands a verb, from the verb, noun, preposition and object
+type Parse = fn(&str, &str, &mut String, &str) -> Action;
+
+// the verbs understood by Tokens::parse besides directions: the words for each one, which are
+// also completed and corrected when misspelled, its abbreviations and how it is parsed
+const VERBS: &[(&[&str], &[&str], Parse)] = &[
+    (&["again"], &["g"], |_, _, _, _| Action::Again),
+    (&["answer", "reply"], &[], |verb, noun, _, _| {
+        if noun.is_empty() {
+            Action::what_do(verb)
+        } else {
+            let (text, to) = speech(noun);
+            Action::Answer(text, to)
+        }
+    }),
+    (
+        &[
+            "attack", "cut", "hit", "hurt", "kill", "murder", "slash", "slay", "stab",
+        ],
+        &[],
+        |verb, noun, prep, obj| {
+            if prep.is_empty() {
+                prep.push_str("with");
+            }
+            match (noun.is_empty(), prep.as_str(), obj.is_empty()) {
+                (false, _, false) => Action::Attack(noun.to_owned(), obj.to_owned()),
+                (true, _, false) => Action::what_do(&format!("{verb} {prep} the {obj}")),
+                (false, _, true) => Action::what_do(&format!("{verb} the {noun} {prep}")),
+                (true, _, true) => Action::what_do(verb),
+            }
+        },
+    ),
+    (&["break", "destroy", "smash"], &[], |_, _, _, _| {
+        Action::Break(String::new())
+    }),
+    (&["climb"], &[], |_, _, _, _| Action::Climb),
+    (&["close", "shut"], &[], |verb, noun, _, _| {
+        do_or_ask!(Close, noun, verb)
+    }),
+    (&["drop", "throw"], &[], |verb, noun, _, obj| {
+        if noun.is_empty() {
+            Action::what_do(verb)
+        } else if obj.is_empty() {
+            Action::Drop(noun.to_owned())
+        } else {
+            Action::Put(noun.to_owned(), obj.to_owned())
+        }
+    }),
+    (
+        &["eat", "consume", "devour", "drink", "quaff"],
+        &[],
+        |verb, noun, _, _| do_or_ask!(Eat, noun, verb),
+    ),
+    (&["enter"], &[], |verb, noun, _, _| {
+        if noun.is_empty() {
+            Action::Walk(verb.to_owned())
+        } else {
+            Action::Walk(noun.to_owned())
+        }
+    }),
+    (
+        &["examine", "inspect", "read"],
+        &["what", "x"],
+        |verb, noun, _, _| do_or_ask!(Examine, noun, verb),
+    ),
+    (&["give"], &[], |_, _, _, _| Action::Give(String::new())),
+    (&["go", "walk"], &[], |verb, noun, prep, _| {
+        if noun.is_empty() {
+            match prep.as_str() {
+                "in" => Action::Walk("enter".to_owned()),
+                "out" => Action::Walk("exit".to_owned()),
+                _ => Action::Clarify(format!("Where do you want to {verb}?")),
+            }
+        } else {
+            Action::Walk(noun.to_owned())
+        }
+    }),
+    (&["hello"], &["hi"], |_, _, _, _| Action::Hello),
+    (&["help"], &[], |_, _, _, _| Action::Help),
+    (&[], &["in"], |_, _, _, _| Action::Walk("enter".to_owned())),
+    (&["inventory"], &["i"], |_, _, _, _| Action::Inventory),
+    (&["light"], &[], |_, _, _, _| Action::Light(String::new())),
+    (&["look"], &["l"], |_, noun, _, _| {
+        if noun.is_empty() {
+            Action::Look
+        } else {
+            Action::Examine(noun.to_owned())
+        }
+    }),
+    (&["move", "pull", "push"], &[], |verb, noun, _, _| {
+        if noun.is_empty() {
+            Action::what_do(verb)
+        } else {
+            Action::Move(noun.to_owned())
+        }
+    }),
+    (&["open"], &[], |verb, noun, _, _| {
+        do_or_ask!(Open, noun, verb)
+    }),
+    (&[], &["out"], |_, _, _, _| Action::Walk("exit".to_owned())),
+    (&["pick"], &[], |_, noun, _, _| match noun {
+        "" => Action::what_do("pick"),
+        noun if noun.starts_with("up ") => Action::Take(noun[2..].to_string()),
+        _ => Action::Take(noun.to_owned()),
+    }),
+    (&["put", "place"], &[], |verb, noun, prep, obj| {
+        if prep.is_empty() {
+            prep.push_str("in");
+        }
+        match (noun.is_empty(), prep.as_str(), obj.is_empty()) {
+            (false, "on", true) => Action::Wear(noun.to_owned()),
+            (true, "on", false) => Action::Wear(obj.to_owned()),
+            (true, "on", true) => Action::what_do("put on"),
+            (false, _, false) => Action::Put(noun.to_owned(), obj.to_owned()),
+            (true, _, false) => Action::what_do(&format!("{verb} {prep} the {obj}")),
+            (false, _, true) => Action::what_do(&format!("{verb} the {noun} {prep}")),
+            (true, _, true) => Action::what_do(verb),
+        }
+    }),
+    (
+        &["say", "shout", "speak", "yell"],
+        &[],
+        |verb, noun, _, _| {
+            if noun.is_empty() {
+                Action::what_do(verb)
+            } else {
+                let (text, to) = speech(noun);
+                Action::Say(text, to)
+            }
+        },
+    ),
+    (
+        &["take", "get", "grab", "hold", "remove"],
+        &[],
+        |verb, noun, _, _| do_or_ask!(Take, noun, verb),
+    ),
+    (&["version"], &[], |_, _, _, _| Action::Version),
+    (&["wait", "sleep"], &["z"], |_, _, _, _| Action::Sleep),
+    (&["wear", "don"], &[], |_, noun, _, _| {
+        Action::Wear(noun.to_owned())
+    }),
+    (&["where", "find", "see"], &[], |_, noun, _, _| {
+        if noun.is_empty() {
+            Action::NoVerb
+        } else {
+            Action::Where(noun.to_owned())
+        }
+    }),
+    (&["write", "inscribe"], &[], |verb, noun, prep, obj| {
+        if prep.is_empty() {
+            prep.push_str("on");
+        }
+        match (noun.is_empty(), obj.is_empty()) {
+            (false, false) => Action::Write(noun.to_owned(), obj.to_owned()),
+            (true, false) => Action::what_do(&format!("{verb} {prep} the {obj}")),
+            (false, true) => Action::what_do(&format!("{verb} {noun} {prep}")),
+            (true, true) => Action::what_do(verb),
+        }
+    }),
+];
+
+// the words that can start a command, without abbreviations
+fn verb_words() -> impl Iterator<Item = &'static str> {
+    VERBS
+        .iter()
+        .flat_map(|(words, _, _)| words.iter().copied())
+        .chain(DIRECTIONS)
+}
+
+// whether Tokens::parse understands a word as a verb
+pub(crate) fn is_verb(word: &str) -> bool {
+    word.is_direction()
+        || VERBS
+            .iter()
+            .any(|(words, abbreviations, _)| words.contains(&word) || abbreviations.contains(&word))
+}
+
+// the ways to finish the input, by verb for the first word and by item name after it
+pub(crate) fn complete(input: &str, verbs: &[String], names: &[String]) -> Vec<String> {
+    let input = input.to_lowercase();
+    let mut words = input.split_whitespace().collect::<Vec<_>>();
+    // the word being completed, which is empty after a space
+    let partial = if input.ends_with(char::is_whitespace) {
+        ""
+    } else {
+        words.pop().unwrap_or_default()
+    };
+
+    let mut candidates = if words.is_empty() {
+        verb_words()
+            .map(str::to_owned)
+            .chain(verbs.iter().cloned())
+            .filter(|verb| verb.starts_with(partial))
+            .collect()
+    } else {
+        // complete as much of the end of the input as matches a name
+        (1..=words.len())
+            .map(|start| {
+                let prefix = words[..start].join(" ");
+                let typed = words[start..]
+                    .iter()
+                    .chain([&partial])
+                    .copied()
+                    .collect::<Vec<_>>()
+                    .join(" ");
+
+                names
+                    .iter()
+                    .map(|name| name.to_lowercase())
+                    .filter(|name| {
+                        name.starts_with(&typed)
+                            || (!typed.contains(' ')
+                                && name.split_whitespace().any(|w| w.starts_with(&typed)))
+                    })
+                    .map(|name| format!("{prefix} {name}"))
+                    .collect::<Vec<_>>()
+            })
+            .find(|candidates| !candidates.is_empty())
+            .unwrap_or_default()
+    };
+
+    candidates.sort();
+    candidates.dedup();
+    candidates
+}
+
+// the number of single character edits, counting swapping two neighbors as one, to turn a into b
+pub(crate) fn distance(a: &str, b: &str) -> usize {
+    let a = a.chars().collect::<Vec<_>>();
+    let b = b.chars().collect::<Vec<_>>();
+    let mut d = vec![vec![0; b.len() + 1]; a.len() + 1];
+
+    d[0] = (0..=b.len()).collect();
+    for (i, row) in d.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            d[i][j] = (d[i - 1][j] + 1)
+                .min(d[i][j - 1] + 1)
+                .min(d[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                d[i][j] = d[i][j].min(d[i - 2][j - 2] + 1);
+            }
+        }
+    }
+
+    d[a.len()][b.len()]
+}
+
+// how many edits a word can be from what it was meant to be, which is none for very short words
+pub(crate) fn tolerance(word: &str) -> usize {
+    match word.chars().count() {
+        0..=2 => 0,
+        3..=5 => 1,
+        _ => 2,
+    }
+}
+
+// the known verbs closest to a misspelled one
+pub(crate) fn similar_verbs(verb: &str) -> Vec<&'static str> {
+    let distances = verb_words()
+        .map(|known| (known, distance(verb, known)))
+        .collect::<Vec<_>>();
+    let Some(&closest) = distances.iter().map(|(_, d)| d).min() else {
+        return Vec::new();
+    };
+
+    if closest > tolerance(verb) {
+        Vec::new()
+    } else {
+        distances
+            .into_iter()
+            .filter(|(_, d)| *d == closest)
+            .map(|(verb, _)| verb)
+            .collect()
+    }
+}
+
 fn alias(s: &str) -> &str {
     match s {
         "n" => "north",
@@ -23,19 +318,125 @@ fn alias(s: &str) -> &str {
         "inside" => "in",
         "outside" => "out",
         "everything" => "all",
-        "them" => "it",
         _ => s,
     }
 }
 
-macro_rules! do_or_ask {
-    ($action:ident, $noun:ident, $verb:ident) => {
-        if $noun.is_empty() {
-            Action::what_do($verb)
+// the words of a phrase as they are understood, without the words that don't matter
+pub(crate) fn normalize(s: &str) -> Vec<String> {
+    split_quoted(s)
+        .into_iter()
+        .filter_map(|w| {
+            if w.starts_with('"') {
+                return Some(w);
+            }
+
+            let w = w.to_lowercase();
+            let w = alias(&w);
+            (!USELESS_WORDS.contains(&w)).then(|| w.to_owned())
+        })
+        .collect()
+}
+
+// split at whitespace, except that quoted text is kept as it is in one word in double quotes
+fn split_quoted(s: &str) -> Vec<String> {
+    let chars = s.chars().collect::<Vec<_>>();
+    let mut words = Vec::new();
+    let mut word = String::new();
+
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+
+        // a closing ' must end a word so that "don't" is not a quote
+        let end = if (c == '"' || c == '\'') && word.is_empty() {
+            (i + 1..chars.len())
+                .find(|&j| {
+                    chars[j] == c
+                        && (c == '"' || chars.get(j + 1).is_none_or(|n| !n.is_alphanumeric()))
+                })
+                .or((c == '"').then_some(chars.len()))
         } else {
-            Action::$action($noun.to_owned())
+            None
+        };
+
+        if let Some(end) = end {
+            words.push(format!(
+                "\"{}\"",
+                chars[i + 1..end].iter().collect::<String>()
+            ));
+            i = end + 1;
+        } else {
+            if c.is_whitespace() {
+                if !word.is_empty() {
+                    words.push(mem::take(&mut word));
+                }
+            } else {
+                word.push(c);
+            }
+            i += 1;
         }
-    };
+    }
+    if !word.is_empty() {
+        words.push(word);
+    }
+
+    words
+}
+
+// what is said and who it is said to, as in "'parley' captain" or "captain 'parley'"
+fn speech(noun: &str) -> (String, String) {
+    let words = split_quoted(noun);
+
+    match words.iter().position(|w| w.starts_with('"')) {
+        Some(i) => {
+            let to = [&words[..i], &words[i + 1..]].concat().join(" ");
+            (words[i].to_owned(), to)
+        }
+        None => (noun.to_owned(), String::new()),
+    }
+}
+
+// quoted text without its quotes
+pub(crate) fn unquote(s: &str) -> &str {
+    s.strip_prefix('"')
+        .and_then(|s| s.strip_suffix('"'))
+        .unwrap_or(s)
+}
+
+// the words of input in lowercase with punctuation as "and", except for quoted text
+pub(crate) fn words(input: &str) -> Vec<String> {
+    split_quoted(input)
+        .into_iter()
+        .flat_map(|w| {
+            if w.starts_with('"') {
+                vec![w]
+            } else {
+                w.replace([',', ';', '.'], " and ")
+                    .split_whitespace()
+                    .map(str::to_lowercase)
+                    .collect()
+            }
+        })
+        .collect()
+}
+
+// the position in a list, from 1, that a word like "second", "2nd" or "2" refers to
+pub(crate) fn ordinal(word: &str) -> Option<usize> {
+    const ORDINALS: [&str; 10] = [
+        "first", "second", "third", "fourth", "fifth", "sixth", "seventh", "eighth", "ninth",
+        "tenth",
+    ];
+
+    if let Some(i) = ORDINALS.iter().position(|ordinal| *ordinal == word) {
+        return Some(i + 1);
+    }
+
+    let number = ["st", "nd", "rd", "th"]
+        .iter()
+        .find_map(|suffix| word.strip_suffix(suffix))
+        .unwrap_or(word);
+    number.parse().ok().filter(|&n| n > 0)
 }
 
 #[derive(Clone, Default, Debug, Deserialize, Serialize)]
@@ -86,115 +487,68 @@ impl Tokens {
         }
     }
 
+    // what the player is doing, as in "(taking the sword)"
+    pub(crate) fn describe(&self) -> String {
+        if let Action::Walk(direction) = &self.action {
+            return if direction.is_direction() {
+                format!("going {direction}")
+            } else {
+                format!("going to the {direction}")
+            };
+        }
+
+        let verb = match self.verb.as_str() {
+            "l" => "look",
+            "x" => "examine",
+            verb => verb,
+        };
+        let mut description = if verb.ends_with('e') && !verb.ends_with("ee") {
+            format!("{}ing", &verb[..verb.len() - 1])
+        } else if matches!(
+            verb,
+            "cut" | "drop" | "get" | "grab" | "hit" | "put" | "shut" | "stab"
+        ) {
+            format!("{verb}{}ing", &verb[verb.len() - 1..])
+        } else {
+            format!("{verb}ing")
+        };
+        if !self.noun.is_empty() {
+            description = format!("{description} the {}", self.noun);
+        }
+        if !self.obj.is_empty() {
+            description = format!("{description} {} the {}", self.prep, self.obj);
+        }
+        description
+    }
+
     pub(crate) fn noun(&self) -> &str {
         self.noun.as_ref()
     }
 
+    // the noun phrases of a command with several, as in "take the apple and orange"
+    pub(crate) fn nouns(&self) -> Vec<&str> {
+        if self.noun == "all" || self.noun.starts_with("all ") || self.noun.starts_with('"') {
+            vec![&self.noun]
+        } else {
+            self.noun.split(" and ").collect()
+        }
+    }
+
     pub(crate) fn obj(&self) -> &str {
         self.obj.as_ref()
     }
 
     fn parse(verb: &str, noun: &str, prep: &mut String, obj: &str) -> Action {
-        match verb {
-            _ if verb.is_direction() => Action::Walk(verb.to_owned()),
-            "again" | "g" => Action::Again,
-            "attack" | "cut" | "hit" | "hurt" | "kill" | "murder" | "slash" | "slay" | "stab" => {
-                if prep.is_empty() {
-                    prep.push_str("with");
-                }
-                match (noun.is_empty(), prep.as_str(), obj.is_empty()) {
-                    (false, _, false) => Action::Attack(noun.to_owned(), obj.to_owned()),
-                    (true, _, false) => Action::what_do(&format!("{verb} {prep} the {obj}")),
-                    (false, _, true) => Action::what_do(&format!("{verb} the {noun} {prep}")),
-                    (true, _, true) => Action::what_do(verb),
-                }
-            }
-            "break" | "destroy" | "smash" => Action::Break(String::new()),
-            "climb" => Action::Climb,
-            "close" | "shut" => do_or_ask!(Close, noun, verb),
-            "drop" | "throw" => {
-                if noun.is_empty() {
-                    Action::what_do(verb)
-                } else if obj.is_empty() {
-                    Action::Drop(noun.to_owned())
-                } else {
-                    Action::Put(noun.to_owned(), obj.to_owned())
-                }
-            }
-            "eat" | "consume" | "devour" | "drink" | "quaff" => do_or_ask!(Eat, noun, verb),
-            "enter" => {
-                if noun.is_empty() {
-                    Action::Walk(verb.to_owned())
-                } else {
-                    Action::Walk(noun.to_owned())
-                }
-            }
-            "examine" | "inspect" | "read" | "what" | "x" => do_or_ask!(Examine, noun, verb),
-            "go" | "walk" => {
-                if noun.is_empty() {
-                    match prep.as_str() {
-                        "in" => Action::Walk("enter".to_owned()),
-                        "out" => Action::Walk("exit".to_owned()),
-                        _ => Action::Clarify(format!("Where do you want to {verb}?")),
-                    }
-                } else {
-                    Action::Walk(noun.to_owned())
-                }
-            }
-            "give" => todo!(),
-            "hello" | "hi" => Action::Hello,
-            "help" => Action::Help,
-            "in" => Action::Walk("enter".to_owned()),
-            "inventory" | "i" => Action::Inventory,
-            "light" => todo!(),
-            "look" | "l" => {
-                if noun.is_empty() {
-                    Action::Look
-                } else {
-                    Action::Examine(noun.to_owned())
-                }
-            }
-            "move" | "pull" | "push" => {
-                if noun.is_empty() {
-                    Action::what_do(verb)
-                } else {
-                    Action::Move(noun.to_owned())
-                }
-            }
-            "open" => do_or_ask!(Open, noun, verb),
-            "out" => Action::Walk("exit".to_owned()),
-            "pick" => match noun {
-                "" => Action::what_do("pick"),
-                noun if noun.starts_with("up ") => Action::Take(noun[2..].to_string()),
-                _ => Action::Take(noun.to_owned()),
-            },
-            "put" | "place" => {
-                if prep.is_empty() {
-                    prep.push_str("in");
-                }
-                match (noun.is_empty(), prep.as_str(), obj.is_empty()) {
-                    (false, "on", true) => Action::Wear(noun.to_owned()),
-                    (true, "on", false) => Action::Wear(obj.to_owned()),
-                    (true, "on", true) => Action::what_do("put on"),
-                    (false, _, false) => Action::Put(noun.to_owned(), obj.to_owned()),
-                    (true, _, false) => Action::what_do(&format!("{verb} {prep} the {obj}")),
-                    (false, _, true) => Action::what_do(&format!("{verb} the {noun} {prep}")),
-                    (true, _, true) => Action::what_do(verb),
-                }
-            }
-            "take" | "get" | "grab" | "hold" | "remove" => do_or_ask!(Take, noun, verb),
-            "version" => Action::Version,
-            "wait" | "z" | "sleep" => Action::Sleep,
-            "wear" | "don" => Action::Wear(noun.to_owned()),
-            "where" | "find" | "see" => {
-                if noun.is_empty() {
-                    Action::NoVerb
-                } else {
-                    Action::Where(noun.to_owned())
-                }
-            }
-            "" => Action::NoVerb,
-            _ => Action::Unknown(verb.to_owned()),
+        if verb.is_direction() {
+            return Action::Walk(verb.to_owned());
+        }
+
+        match VERBS.iter().find(|(words, abbreviations, _)| {
+            words.contains(&verb) || abbreviations.contains(&verb)
+        }) {
+            Some((_, _, parse)) => parse(verb, noun, prep, obj),
+            None if verb.is_empty() => Action::NoVerb,
+            None => Action::Unknown(verb.to_owned()),
         }
     }
 
@@ -207,18 +561,8 @@ impl Tokens {
     }
 
     pub(crate) fn with(verb: String, noun: String, mut prep: String, obj: String) -> Self {
-        let noun = noun
-            .split_whitespace()
-            .map(alias)
-            .filter(|w| !USELESS_WORDS.contains(w))
-            .collect::<Vec<_>>()
-            .join(" ");
-        let obj = obj
-            .split_whitespace()
-            .map(alias)
-            .filter(|w| !USELESS_WORDS.contains(w))
-            .collect::<Vec<_>>()
-            .join(" ");
+        let noun = normalize(&noun).join(" ");
+        let obj = normalize(&obj).join(" ");
 
         Self {
             action: Self::parse(&verb, &noun, &mut prep, &obj),
@@ -0,0 +1,260 @@
+use std::{convert::Infallible, error, fmt, str::FromStr};
+
+use crate::game::Game;
+
+const SAVE_PREFIX: &str = "# save: ";
+
+/// A recording of the commands given to a Game and the output of each
+///
+/// Transcripts are plain text, so they can also be written by hand as walkthroughs:
+/// ```text
+/// # lines starting with '#' are comments
+/// > take sword
+/// Taken.
+///
+/// > look
+/// Closet
+/// You are in the *.
+/// ...
+/// ```
+/// Each command starts with `> ` and is followed by its expected output.
+/// A `*` matches any text within a line and a line of `...` matches any number of lines.
+///
+/// Transcripts recorded by `script` start with the saved state of the Game. They have no
+/// comments or wildcards, so their output must match exactly.
+#[derive(Debug, Default)]
+pub struct Transcript {
+    save: String,
+    // whether the output was recorded and so must match exactly
+    exact: bool,
+    entries: Vec<Entry>,
+}
+
+#[derive(Debug)]
+struct Entry {
+    input: String,
+    output: String,
+}
+
+/// A recorded command whose output differs from the recording
+#[derive(Debug)]
+pub struct Mismatch {
+    /// The recorded command
+    pub input: String,
+    /// The recorded output
+    pub expected: String,
+    /// The output of the Game
+    pub actual: String,
+}
+
+/// The commands of a walkthrough or recorded transcript whose output differs from it
+#[derive(Debug)]
+pub struct WalkthroughError {
+    /// The commands whose output differs
+    pub mismatches: Vec<Mismatch>,
+    /// The number of commands in the transcript
+    pub commands: usize,
+}
+
+impl fmt::Display for WalkthroughError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for mismatch in &self.mismatches {
+            write!(f, "{mismatch}\n\n")?;
+        }
+        write!(
+            f,
+            "{} of {} commands differ from the transcript.",
+            self.mismatches.len(),
+            self.commands
+        )
+    }
+}
+
+impl error::Error for WalkthroughError {}
+
+impl fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "> {}", self.input)?;
+        for line in self.expected.lines() {
+            write!(f, "\n- {line}")?;
+        }
+        for line in self.actual.lines() {
+            write!(f, "\n+ {line}")?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Transcript {
+    type Err = Infallible;
+
+    /// Read a Transcript written while scripting
+    /// ```
+    /// # use kingslayer::Transcript;
+    /// let transcript: Transcript = "> look\nCenter Room".parse().unwrap();
+    /// ```
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut transcript = Self {
+            exact: s.starts_with(SAVE_PREFIX),
+            ..Self::default()
+        };
+
+        for line in s.lines() {
+            if let Some(save) = line.strip_prefix(SAVE_PREFIX) {
+                if transcript.entries.is_empty() {
+                    transcript.save = save.to_owned();
+                    continue;
+                }
+            }
+
+            if line.starts_with('#') && !transcript.exact {
+                continue;
+            } else if let Some(input) = command(line) {
+                transcript.entries.push(Entry {
+                    input: input.to_owned(),
+                    output: String::new(),
+                });
+            } else if let Some(entry) = transcript.entries.last_mut() {
+                entry.output.push_str(line);
+                entry.output.push('\n');
+            }
+        }
+
+        for entry in &mut transcript.entries {
+            entry.output.truncate(entry.output.trim_end().len());
+        }
+
+        Ok(transcript)
+    }
+}
+
+impl Transcript {
+    /// Give the recorded commands to a Game and return every output that differs from the recording.
+    ///
+    /// If the Transcript was started partway through a game, the Game is first restored to that point.
+    /// ```
+    /// # use kingslayer::{Game, Transcript};
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let transcript: Transcript = "> jump\nI do not know the verb \"jump\".".parse()?;
+    /// assert!(transcript.replay(&mut Game::default())?.is_empty());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn replay(&self, game: &mut Game) -> Result<Vec<Mismatch>, Box<dyn error::Error>> {
+        if !self.save.is_empty() {
+            game.restore_from_str(&self.save)?;
+        }
+
+        Ok(self
+            .entries
+            .iter()
+            .filter_map(|entry| {
+                let actual = game.ask(&entry.input);
+                let matched = if self.exact {
+                    entry.output == actual.trim_end()
+                } else {
+                    matches(
+                        &entry.output.lines().collect::<Vec<_>>(),
+                        &actual.lines().collect::<Vec<_>>(),
+                    )
+                };
+
+                if matched {
+                    None
+                } else {
+                    Some(Mismatch {
+                        input: entry.input.clone(),
+                        expected: entry.output.clone(),
+                        actual,
+                    })
+                }
+            })
+            .collect())
+    }
+
+    /// The number of recorded commands
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether there are no recorded commands
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+/// Play a walkthrough against a RON world and fail with a `WalkthroughError` listing every
+/// output that differs from it.
+/// ```
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let world = include_str!("world.ron");
+/// kingslayer::test_walkthrough(world, "> take stick\nTaken.\n\n> i\nYou are carrying:\n*")?;
+///
+/// let err = kingslayer::test_walkthrough(world, "> take stick\nNice try.").unwrap_err();
+/// assert_eq!(
+///     err.to_string(),
+///     "> take stick\n- Nice try.\n+ Taken.\n\n1 of 1 commands differ from the transcript."
+/// );
+/// # Ok(())
+/// # }
+/// ```
+pub fn test_walkthrough(world: &str, walkthrough: &str) -> Result<(), Box<dyn error::Error>> {
+    let mut game: Game = world.parse()?;
+    let transcript: Transcript = walkthrough.parse()?;
+
+    let mismatches = transcript.replay(&mut game)?;
+    if mismatches.is_empty() {
+        Ok(())
+    } else {
+        Err(WalkthroughError {
+            mismatches,
+            commands: transcript.len(),
+        }
+        .into())
+    }
+}
+
+fn command(line: &str) -> Option<&str> {
+    if line == ">" {
+        Some("")
+    } else {
+        line.strip_prefix("> ")
+    }
+}
+
+// whether the lines of output match the expected lines, where a line of "..." matches any lines
+fn matches(expected: &[&str], actual: &[&str]) -> bool {
+    match (expected.first(), actual.first()) {
+        (None, None) => true,
+        (Some(&"..."), _) => {
+            (0..=actual.len()).any(|skip| matches(&expected[1..], &actual[skip..]))
+        }
+        (Some(pattern), Some(line)) => {
+            line_matches(pattern.as_bytes(), line.as_bytes())
+                && matches(&expected[1..], &actual[1..])
+        }
+        _ => false,
+    }
+}
+
+// whether the line matches the pattern, where '*' matches any text
+fn line_matches(pattern: &[u8], line: &[u8]) -> bool {
+    match (pattern.first(), line.first()) {
+        (None, None) => true,
+        (Some(b'*'), _) => (0..=line.len()).any(|skip| line_matches(&pattern[1..], &line[skip..])),
+        (Some(p), Some(l)) => p == l && line_matches(&pattern[1..], &line[1..]),
+        _ => false,
+    }
+}
+
+// the start of a transcript recorded from a game in the given saved state
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn header(save: &str) -> String {
+    format!("{SAVE_PREFIX}{save}\n")
+}
+
+// a single command and its output as written to a transcript
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn entry(input: &str, output: &str) -> String {
+    format!("\n> {input}\n{output}\n")
+}
@@ -0,0 +1,183 @@
+use std::{
+    cell::RefCell,
+    error,
+    io::{self, BufReader, Read, Stdout, Write},
+};
+
+use crossterm::{
+    cursor::MoveTo,
+    event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+    execute, queue,
+    style::{Attribute, Print, SetAttribute},
+    terminal::{self, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen},
+};
+
+use crate::{
+    game::Game,
+    screen::{Edit, Screen},
+};
+
+/// A full-screen terminal with a status line, an output pane and an input line
+///
+/// Reading from it edits a line of input and writing to it adds to the output pane.
+/// Output after the last newline is shown as the prompt of the input line.
+struct Tui {
+    screen: RefCell<Screen>,
+}
+
+impl Tui {
+    fn new() -> io::Result<Self> {
+        terminal::enable_raw_mode()?;
+        execute!(io::stdout(), EnterAlternateScreen)?;
+
+        Ok(Self {
+            screen: RefCell::default(),
+        })
+    }
+
+    // show the state of the Game in the status line and complete from what is visible
+    fn update(&self, game: &Game) {
+        let mut screen = self.screen.borrow_mut();
+        screen.status = (
+            game.room().to_owned(),
+            format!("HP: {}  Turns: {}", game.hp(), game.turns()),
+        );
+        screen.verbs = game.verb_names();
+        screen.names = game.visible_names();
+    }
+
+    // wait for a single key press
+    fn pause(&self) -> io::Result<()> {
+        draw(&mut self.screen.borrow_mut(), &mut io::stdout())?;
+
+        loop {
+            if let Event::Key(KeyEvent {
+                kind: KeyEventKind::Press,
+                ..
+            }) = event::read()?
+            {
+                return Ok(());
+            }
+        }
+    }
+}
+
+impl Drop for Tui {
+    fn drop(&mut self) {
+        let _ = execute!(io::stdout(), LeaveAlternateScreen);
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+impl Read for &Tui {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let mut screen = self.screen.borrow_mut();
+        let mut stdout = io::stdout();
+
+        while screen.line.is_empty() && !screen.eof {
+            draw(&mut screen, &mut stdout)?;
+            match event::read()? {
+                Event::Key(key) if key.kind != KeyEventKind::Release => {
+                    if let Some(edit) = edit(key) {
+                        screen.edit(edit);
+                    }
+                }
+                _ => (),
+            }
+        }
+
+        let n = buf.len().min(screen.line.len());
+        buf[..n].copy_from_slice(&screen.line[..n]);
+        screen.line.drain(..n);
+        Ok(n)
+    }
+}
+
+impl Write for &Tui {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.screen
+            .borrow_mut()
+            .write(&String::from_utf8_lossy(buf));
+
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        draw(&mut self.screen.borrow_mut(), &mut io::stdout())
+    }
+}
+
+fn draw(screen: &mut Screen, stdout: &mut Stdout) -> io::Result<()> {
+    let (width, height) = terminal::size()?;
+    let (width, height) = (usize::from(width).max(1), usize::from(height));
+
+    queue!(
+        stdout,
+        Clear(ClearType::All),
+        MoveTo(0, 0),
+        SetAttribute(Attribute::Reverse),
+        Print(screen.status_line(width)),
+        SetAttribute(Attribute::Reset)
+    )?;
+
+    let rows = screen.output_rows(width, height.saturating_sub(2));
+    for (row, line) in rows.iter().enumerate() {
+        queue!(stdout, MoveTo(0, row as u16 + 1), Print(line))?;
+    }
+
+    let (input, cursor) = screen.input_line(width);
+    let bottom = height.saturating_sub(1) as u16;
+    queue!(
+        stdout,
+        MoveTo(0, bottom),
+        Print(input),
+        MoveTo(cursor as u16, bottom)
+    )?;
+
+    stdout.flush()
+}
+
+// the edit a key makes, if any
+fn edit(key: KeyEvent) -> Option<Edit> {
+    let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+
+    Some(match key.code {
+        KeyCode::Enter => Edit::Submit,
+        KeyCode::Tab => Edit::Complete,
+        KeyCode::Char('c') if ctrl => Edit::Interrupt,
+        KeyCode::Char('d') if ctrl => Edit::EndOfInput,
+        KeyCode::Char('a') if ctrl => Edit::Home,
+        KeyCode::Char('e') if ctrl => Edit::End,
+        KeyCode::Char('u') if ctrl => Edit::Kill,
+        KeyCode::Char(c) if !ctrl => Edit::Insert(c),
+        KeyCode::Backspace => Edit::Backspace,
+        KeyCode::Delete => Edit::Delete,
+        KeyCode::Left => Edit::Left,
+        KeyCode::Right => Edit::Right,
+        KeyCode::Home => Edit::Home,
+        KeyCode::End => Edit::End,
+        KeyCode::Up => Edit::Up,
+        KeyCode::Down => Edit::Down,
+        KeyCode::PageUp => Edit::PageUp,
+        KeyCode::PageDown => Edit::PageDown,
+        _ => return None,
+    })
+}
+
+impl Game {
+    // play in a full-screen terminal, keeping the status line up to date before each prompt
+    pub(crate) fn play_tui(&mut self) -> Result<(), Box<dyn error::Error>> {
+        let tui = Tui::new()?;
+
+        tui.update(self);
+        self.play_loop(BufReader::new(&tui), &tui, true, |game| tui.update(game))?;
+
+        if self.is_dead() {
+            tui.update(self);
+            writeln!(&tui, "\nPress any key to exit.")?;
+            tui.pause()?;
+        }
+
+        Ok(())
+    }
+}
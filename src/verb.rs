@@ -0,0 +1,118 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::tokens;
+
+/// A verb declared by a world
+/// ```ron
+/// verbs: [
+///     (names: ["shove"], means: "push"),
+///     (
+///         names: ["sail", "voyage"],
+///         rules: [
+///             (pattern: "to *", items: ["ISLAND"], response: "You sail away.", go: "ISLAND"),
+///             (pattern: "", response: "Where do you want to sail?"),
+///         ],
+///     ),
+/// ]
+/// ```
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(default)]
+pub(crate) struct Verb {
+    names: Vec<String>,
+    // a built-in verb this one is a synonym of
+    means: String,
+    rules: Vec<Rule>,
+}
+
+/// What a verb does when the words after it match a pattern
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(default)]
+pub(crate) struct Rule {
+    // the words after the verb, where each '*' stands for the name of a visible item
+    pattern: String,
+    // the item each '*' must be, in order, or any visible item if there are fewer
+    items: Vec<String>,
+    pub(crate) response: String,
+    // where the player goes
+    pub(crate) go: String,
+    // where items go, by item
+    pub(crate) moves: BTreeMap<String, String>,
+}
+
+impl Verb {
+    // lowercase the names and what the verb means, as input is lowercased before it is matched
+    pub(crate) fn lowercase(&mut self) {
+        for name in &mut self.names {
+            *name = name.to_lowercase();
+        }
+        self.means = self.means.to_lowercase();
+    }
+
+    pub(crate) fn is_named(&self, verb: &str) -> bool {
+        self.names.iter().any(|name| name == verb)
+    }
+
+    pub(crate) fn means(&self) -> &str {
+        &self.means
+    }
+
+    pub(crate) fn names(&self) -> &[String] {
+        &self.names
+    }
+
+    pub(crate) fn rules(&self) -> &[Rule] {
+        &self.rules
+    }
+
+    // the items the rules refer to, which must be in the world
+    pub(crate) fn targets(&self) -> impl Iterator<Item = &str> {
+        self.rules
+            .iter()
+            .flat_map(|rule| {
+                rule.items
+                    .iter()
+                    .chain(rule.moves.keys())
+                    .chain(rule.moves.values())
+                    .chain([&rule.go])
+            })
+            .map(String::as_str)
+            .filter(|id| !id.is_empty())
+    }
+}
+
+impl Rule {
+    // the required item for each noun in the words if they fit the pattern, with "" for any item
+    pub(crate) fn captures(&self, words: &[String]) -> Option<Vec<(String, String)>> {
+        let pattern = tokens::normalize(&self.pattern);
+        let captures = capture(&pattern, words)?;
+
+        Some(
+            captures
+                .into_iter()
+                .enumerate()
+                .map(|(i, noun)| (noun, self.items.get(i).cloned().unwrap_or_default()))
+                .collect(),
+        )
+    }
+}
+
+// the words captured by each '*' of the pattern, which captures at least one word
+fn capture(pattern: &[String], words: &[String]) -> Option<Vec<String>> {
+    match pattern.split_first() {
+        None => words.is_empty().then(Vec::new),
+        Some((first, rest)) if first == "*" => (1..=words.len()).find_map(|len| {
+            let mut captures = capture(rest, &words[len..])?;
+            captures.insert(0, words[..len].join(" "));
+            Some(captures)
+        }),
+        Some((first, rest)) => {
+            if words.first() == Some(first) {
+                capture(rest, &words[1..])
+            } else {
+                None
+            }
+        }
+    }
+}
@@ -0,0 +1,72 @@
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+#include "kingslayer.h"
+
+#define CHECK(cond)                                               \
+    if (!(cond)) {                                                \
+        fprintf(stderr, "%s:%d: %s\n", __FILE__, __LINE__, #cond); \
+        return 1;                                                 \
+    }
+
+static char *read_file(const char *path) {
+    FILE *file = fopen(path, "rb");
+    if (!file) {
+        return NULL;
+    }
+    fseek(file, 0, SEEK_END);
+    long len = ftell(file);
+    rewind(file);
+
+    char *buf = malloc(len + 1);
+    buf[fread(buf, 1, len, file)] = '\0';
+    fclose(file);
+    return buf;
+}
+
+int main(int argc, char **argv) {
+    CHECK(argc == 2);
+
+    char *world = read_file(argv[1]);
+    CHECK(world != NULL);
+    KingslayerGame *game = kingslayer_new(world);
+    free(world);
+    CHECK(game != NULL);
+
+    char *output = kingslayer_ask(game, "look");
+    CHECK(strncmp(output, "Center Room\n", 12) == 0);
+    kingslayer_free_string(output);
+
+    output = kingslayer_ask(game, "open box");
+    CHECK(strcmp(output, "Opening the box reveals a apple.") == 0);
+    kingslayer_free_string(output);
+
+    char *save = kingslayer_save(game);
+    CHECK(save != NULL);
+
+    output = kingslayer_ask(game, "take apple");
+    CHECK(strcmp(output, "Taken.") == 0);
+    kingslayer_free_string(output);
+
+    CHECK(kingslayer_restore(game, save) == 0);
+    kingslayer_free_string(save);
+    output = kingslayer_ask(game, "i");
+    CHECK(strcmp(output, "Your inventory is empty.") == 0);
+    kingslayer_free_string(output);
+    CHECK(!kingslayer_is_dead(game));
+
+    CHECK(kingslayer_restore(game, "(") == -1);
+    char *error = kingslayer_last_error();
+    CHECK(error != NULL);
+    kingslayer_free_string(error);
+
+    kingslayer_free(game);
+
+    CHECK(kingslayer_new("(") == NULL);
+    game = kingslayer_new(NULL);
+    CHECK(game != NULL);
+    kingslayer_free(game);
+
+    return 0;
+}
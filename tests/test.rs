@@ -1,6 +1,6 @@
 #[cfg(test)]
 mod tests {
-    use kingslayer::Game;
+    use kingslayer::{Game, Transcript};
 
     #[test]
     fn test() {
@@ -11,7 +11,48 @@ mod tests {
         }
 
         // unknown verb
-        assert_eq!(game.ask("jump"), "I do not know the verb \"jump\".")
+        assert_eq!(game.ask("jump"), "I do not know the verb \"jump\".");
+
+        // verbs without anything to do yet
+        assert_eq!(game.ask("give box"), "You can't do that.");
+        assert_eq!(game.ask("light box"), "You can't do that.");
+    }
+
+    #[test]
+    fn aliases() {
+        let mut game: Game = include_str!("world.ron").parse().unwrap();
+
+        assert_eq!(game.ask("alias"), "You have no aliases.");
+        assert_eq!(game.ask("alias get = open box then take apple"), "Aliased.");
+        assert_eq!(game.ask("alias inv i"), "Aliased.");
+        assert_eq!(
+            game.ask("alias big thing = l"),
+            "An alias must be a single word."
+        );
+        assert_eq!(game.ask("alias"), "get = open box then take apple\ninv = i");
+
+        // expanded wherever a command starts
+        assert_eq!(
+            game.ask("get and inv"),
+            "Opening the box reveals a apple.\n\nTaken.\n\nYou are carrying:\n  a apple"
+        );
+
+        // an alias does not expand within itself
+        assert_eq!(game.ask("alias l = l and inv"), "Aliased.");
+        assert_eq!(
+            game.ask("l"),
+            "Center Room\nYou are in the center room.\nThere is a box here.\n\nYou are carrying:\n  a apple"
+        );
+
+        // kept in saves
+        let save = game.save_to_string().unwrap();
+        let mut restored: Game = include_str!("world.ron").parse().unwrap();
+        restored.restore_from_str(&save).unwrap();
+        assert_eq!(restored.ask("alias inv"), "inv = i");
+
+        assert_eq!(game.ask("unalias l"), "Unaliased.");
+        assert_eq!(game.ask("unalias l"), "There is no alias \"l\".");
+        assert!(game.ask("l").ends_with("There is a box here."));
     }
 
     #[test]
@@ -30,6 +71,36 @@ mod tests {
         }
     }
 
+    #[test]
+    fn debug_parse() {
+        let mut game: Game = include_str!("world.ron").parse().unwrap();
+
+        assert_eq!(game.ask("debug parse"), "Parse tracing is on.");
+        assert_eq!(
+            game.ask("open box"),
+            "Opening the box reveals a apple.\n[tokens] verb: \"open\", noun: \"box\", prep: \"\", obj: \"\"\n[action] Open(\"box\")\n[items] box: BOX"
+        );
+
+        // after pronouns are replaced
+        assert_eq!(
+            game.ask("take it"),
+            "Taken.\n[tokens] verb: \"take\", noun: \"apple\", prep: \"\", obj: \"\"\n[action] Take(\"apple\")\n[items] apple: APPLE (chosen)"
+        );
+        assert!(game.ask("take ghost").ends_with("[items] ghost: none"));
+
+        assert_eq!(game.ask("debug parse"), "Parse tracing is off.");
+        assert_eq!(game.ask("drop apple"), "Dropped.");
+
+        game.set_debug_parse(true);
+        assert!(game.ask("l").contains("[action] Look"));
+
+        // a bare verb has no items to list
+        assert_eq!(
+            game.ask("go"),
+            "Where do you want to go?\n[tokens] verb: \"go\", noun: \"\", prep: \"\", obj: \"\"\n[action] Clarify(\"Where do you want to go?\")"
+        );
+    }
+
     #[test]
     fn names() {
         let mut game: Game = include_str!("world.ron").parse().unwrap();
@@ -85,6 +156,413 @@ mod tests {
         );
     }
 
+    #[test]
+    fn complete() {
+        let mut game: Game = include_str!("world.ron").parse().unwrap();
+
+        assert_eq!(game.complete("op"), ["open"]);
+        assert_eq!(game.complete("ea"), ["east", "eat"]);
+        assert_eq!(game.complete("ex"), ["examine", "exit"]);
+        assert_eq!(game.complete("ins"), ["inscribe", "inspect"]);
+        assert!(game.complete("xyzzy").is_empty());
+        assert_eq!(game.complete("open B"), ["open box"]);
+        assert_eq!(game.complete("go no"), ["go north"]);
+        // items that are not visible are not completed
+        assert!(game.complete("take app").is_empty());
+        game.ask("open box");
+        assert_eq!(game.complete("take app"), ["take apple"]);
+
+        game.ask("n");
+        assert_eq!(game.complete("take sw"), ["take iron sword"]);
+        assert_eq!(game.complete("take large red b"), ["take large red block"]);
+        assert_eq!(
+            game.complete("put sword in r"),
+            ["put sword in large red block"]
+        );
+        assert_eq!(
+            game.complete("take "),
+            [
+                "take iron sword",
+                "take large red block",
+                "take myself",
+                "take self"
+            ]
+        );
+    }
+
+    #[test]
+    fn suggestions() {
+        let mut game: Game = include_str!("world.ron").parse().unwrap();
+
+        assert_eq!(
+            game.suggestions(),
+            [
+                "south",
+                "north",
+                "take box",
+                "open box",
+                "look",
+                "inventory"
+            ]
+        );
+        // every suggestion is understood
+        for suggestion in game.suggestions() {
+            let output = game.clone().ask(&suggestion);
+            assert!(!output.contains("do not know") && !output.contains("can't see"));
+        }
+
+        game.ask("s");
+        game.ask("take spear");
+        assert_eq!(
+            game.suggestions(),
+            ["attack goblin with spear", "look", "inventory"]
+        );
+    }
+
+    #[test]
+    fn typos() {
+        let mut game: Game = include_str!("world.ron").parse().unwrap();
+
+        assert_eq!(game.ask("tkae box"), "(taking the box)\nTaken.");
+        assert_eq!(game.ask("jump"), "I do not know the verb \"jump\".");
+        assert_eq!(game.ask("take xyzzy"), "You can't see any xyzzy here.");
+        assert!(game
+            .ask("opne box and tkae aple")
+            .ends_with("\n\n(taking the apple)\nTaken."));
+        assert!(game.ask("nroth").starts_with("(going north)\nCloset"));
+        assert_eq!(
+            game.ask("tkae"),
+            "[assuming you meant \"take\"]\nWhat do you want to take?"
+        );
+        assert_eq!(
+            game.ask("take swrod and blokc"),
+            "(taking the iron sword and large red block)\niron sword: Taken.\nlarge red block: Taken."
+        );
+
+        let mut game: Game = r#"(player: "PLAYER", items: {
+            "PLAYER": (locations: ["ROOM"], hp: 10),
+            "ROOM": (names: ["Room"]),
+            "BALL": (names: ["ball"], locations: ["ROOM"], can_take: true),
+            "BELL": (names: ["bell"], locations: ["ROOM"], can_take: true),
+        })"#
+        .parse()
+        .unwrap();
+
+        assert_eq!(game.ask("take bll"), "Did you mean the ball or the bell?");
+        assert_eq!(game.ask("bell"), "Taken.");
+        assert_eq!(game.ask("i"), "You are carrying:\n  a bell");
+    }
+
+    #[test]
+    fn oops() {
+        let mut game: Game = include_str!("world.ron").parse().unwrap();
+
+        assert_eq!(game.ask("oops apple"), "There is nothing to correct.");
+        assert_eq!(game.ask("jump"), "I do not know the verb \"jump\".");
+        assert!(game.ask("oops look").starts_with("Center Room"));
+
+        game.ask("open box");
+        assert_eq!(game.ask("take fruit"), "You can't see any fruit here.");
+        assert_eq!(game.ask("oops"), "What word did you mean?");
+        assert_eq!(game.ask("oops apple"), "Taken.");
+        assert_eq!(game.ask("oops apple"), "There is nothing to correct.");
+
+        // only the word that was not understood is replaced
+        game.ask("n");
+        assert_eq!(
+            game.ask("take iron spoon"),
+            "You can't see any iron spoon here."
+        );
+        assert_eq!(game.ask("oops sword"), "Taken.");
+    }
+
+    #[test]
+    fn verbs() {
+        let mut game: Game = r#"(player: "PLAYER", verbs: [
+            (names: ["shove"], means: "move"),
+            (
+                names: ["pray", "kneel"],
+                rules: [(pattern: "", response: "You feel at peace.")],
+            ),
+            (
+                names: ["sail"],
+                rules: [
+                    (pattern: "to *", items: ["ISLAND"], response: "You sail away.", go: "ISLAND"),
+                    (pattern: "* to *", items: ["", "ISLAND"], moves: {"BOAT": "ISLAND"}, go: "ISLAND"),
+                ],
+            ),
+        ], items: {
+            "PLAYER": (locations: ["DOCK"], hp: 10),
+            "DOCK": (names: ["Dock"]),
+            "ISLAND": (names: ["Island", "island"], locations: ["DOCK"]),
+            "BOAT": (names: ["boat"], desc: "A boat is moored here.", locations: ["DOCK"]),
+            "ROCK": (names: ["rock"], locations: ["DOCK"], covering: ["COIN"], move_message: "A coin!"),
+            "COIN": (names: ["coin"]),
+        })"#
+        .parse()
+        .unwrap();
+
+        assert_eq!(game.ask("shove rock"), "A coin!");
+        assert_eq!(game.ask("kneel"), "You feel at peace.");
+        assert_eq!(game.ask("again"), "You feel at peace.");
+        assert_eq!(game.ask("pray to boat"), "You cannot pray that.");
+        assert_eq!(game.ask("sail"), "What do you want to sail?");
+        assert_eq!(game.ask("sail to boat"), "You cannot sail that.");
+        assert_eq!(game.ask("sail to cave"), "You can't see any cave here.");
+        assert!(game.ask("sail boat to island").starts_with("Island"));
+        assert!(game.ask("look").contains("boat"));
+        assert_eq!(game.ask("jump"), "I do not know the verb \"jump\".");
+        assert!(game.complete("sa").contains(&"sail".to_owned()));
+
+        // rules can only refer to items in the world
+        let err = r#"(player: "PLAYER", verbs: [
+            (names: ["sail"], rules: [(pattern: "", go: "ISLAND")]),
+        ], items: {
+            "PLAYER": (locations: ["DOCK"], hp: 10),
+            "DOCK": (names: ["Dock"]),
+        })"#
+        .parse::<Game>()
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "2:57: There is no item \"ISLAND\" for the verb \"sail\"."
+        );
+
+        // names match whatever their case, and a verb can only mean a verb that is understood
+        let world = r#"(player: "PLAYER", verbs: [
+            (names: ["Heave"], means: "Push"),
+            (names: ["Sing"], rules: [(pattern: "", response: "La la la.")]),
+            (names: ["croon"], means: "SING"),
+        ], items: {
+            "PLAYER": (locations: ["DOCK"], hp: 10),
+            "DOCK": (names: ["Dock"]),
+            "ROCK": (names: ["rock"], locations: ["DOCK"], covering: ["COIN"], move_message: "A coin!"),
+            "COIN": (names: ["coin"]),
+        })"#;
+        let mut game: Game = world.parse().unwrap();
+        assert_eq!(game.ask("heave rock"), "A coin!");
+        assert_eq!(game.ask("croon"), "La la la.");
+        assert!(game.complete("si").contains(&"sing".to_owned()));
+
+        let err = world.replace("Push", "Shuv").parse::<Game>().unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "2:39: There is no verb \"Shuv\" for \"Heave\" to mean."
+        );
+    }
+
+    #[test]
+    fn responses() {
+        let mut game: Game = r#"(player: "PLAYER", items: {
+            "PLAYER": (locations: ["ROOM"], hp: 10),
+            "ROOM": (names: ["Room"]),
+            "PIRATE": (
+                names: ["pirate"],
+                locations: ["ROOM"],
+                responses: {"push": "You probably shouldn't wake him.", "smell": "Rum."},
+            ),
+            "ROSE": (names: ["rose"], locations: ["ROOM"], can_take: true),
+        })"#
+        .parse()
+        .unwrap();
+
+        assert_eq!(game.ask("push pirate"), "You probably shouldn't wake him.");
+        assert_eq!(game.ask("move pirate"), "You cannot move the pirate.");
+        assert_eq!(game.ask("smell pirate"), "Rum.");
+        assert_eq!(game.ask("again"), "Rum.");
+        assert_eq!(game.ask("smell rose"), "You cannot smell that.");
+        assert_eq!(game.ask("smell parrot"), "You can't see any parrot here.");
+        assert_eq!(game.ask("jump pirate"), "I do not know the verb \"jump\".");
+    }
+
+    #[test]
+    fn which() {
+        let mut game: Game = r#"(player: "PLAYER", items: {
+            "PLAYER": (locations: ["DECK"], hp: 10),
+            "DECK": (names: ["Deck"]),
+            "NORTH DOOR": (
+                names: ["door", "north door"],
+                locations: ["DECK"],
+                container: Closed,
+            ),
+            "CABIN DOOR": (
+                names: ["door", "great cabin door"],
+                locations: ["DECK"],
+                container: Closed,
+            ),
+            "RED COIN": (names: ["coin"], locations: ["DECK"], can_take: true),
+            "BLUE COIN": (names: ["coin"], locations: ["DECK"], can_take: true),
+        })"#
+        .parse()
+        .unwrap();
+
+        let question = "Which door, the great cabin door or the north door?";
+        assert_eq!(game.ask("open door"), question);
+        assert_eq!(game.ask("north one"), "Opened.");
+        assert_eq!(game.ask("open door"), question);
+        assert_eq!(game.ask("the first one"), "Opened.");
+        assert_eq!(game.ask("close door"), question);
+        assert_eq!(game.ask("2"), "Closed.");
+        assert_eq!(game.ask("close door"), question);
+        assert_eq!(game.ask("great"), "Closed.");
+
+        // items with nothing to tell them apart
+        assert_eq!(game.ask("take coin"), "Which coin, the coin or the coin?");
+        assert_eq!(game.ask("last"), "Taken.");
+        assert_eq!(game.ask("take coin"), "Taken.");
+    }
+
+    #[test]
+    fn pronouns() {
+        let mut game: Game = r#"(player: "PLAYER", items: {
+            "PLAYER": (locations: ["DECK"], hp: 10),
+            "DECK": (names: ["Deck"]),
+            "NORTH DOOR": (names: ["door", "north door"], locations: ["DECK"], container: Closed),
+            "CABIN DOOR": (names: ["door", "cabin door"], locations: ["DECK"], container: Closed),
+            "SAILOR": (
+                names: ["sailor"],
+                locations: ["DECK"],
+                gender: Male,
+                responses: {"push": "He pushes back."},
+            ),
+            "CAPTAIN": (
+                names: ["captain"],
+                locations: ["DECK"],
+                gender: Female,
+                responses: {"push": "She glares at you."},
+            ),
+            "COINS": (names: ["coins"], locations: ["DECK"], gender: Plural, can_take: true),
+        })"#
+        .parse()
+        .unwrap();
+
+        // "it" is the door that was meant even though another shares its name
+        assert_eq!(game.ask("open north door"), "Opened.");
+        assert_eq!(game.ask("close it"), "Closed.");
+
+        game.ask("x sailor and x captain and take coins");
+        assert_eq!(game.ask("push him"), "He pushes back.");
+        assert_eq!(game.ask("push her"), "She glares at you.");
+        assert_eq!(game.ask("drop them"), "Dropped.");
+        assert_eq!(game.ask("open it"), "Opened.");
+
+        let save = game.save_to_string().unwrap();
+        game.ask("x cabin door");
+        game.restore_from_str(&save).unwrap();
+        assert_eq!(game.ask("close it"), "Closed.");
+    }
+
+    #[test]
+    fn quotes() {
+        let mut game: Game = r#"(player: "PLAYER", items: {
+            "PLAYER": (locations: ["ROOM"], hp: 10),
+            "ROOM": (names: ["Room"]),
+            "PAPER": (names: ["paper"], locations: ["ROOM"], can_take: true, can_write: true),
+            "ROCK": (names: ["rock"], locations: ["ROOM"], details: "It is grey."),
+        })"#
+        .parse()
+        .unwrap();
+
+        assert_eq!(game.ask("say 'Open Sesame'"), "You say \"Open Sesame\".");
+        assert_eq!(
+            game.ask("say \"Hi, Bob. Don't go\""),
+            "You say \"Hi, Bob. Don't go\"."
+        );
+        assert_eq!(game.ask("say"), "What do you want to say?");
+        assert_eq!(
+            game.ask("answer \"And Then\" and look"),
+            "You answer \"And Then\".\n\nRoom\n"
+        );
+
+        assert_eq!(game.ask("write 'Alice' on the paper"), "Written.");
+        assert_eq!(game.ask("x paper"), "Written on the paper is \"Alice\".");
+        assert_eq!(
+            game.ask("write \"Bob\""),
+            "What do you want to write \"Bob\" on?"
+        );
+        assert_eq!(game.ask("rock"), "You can't write on the rock.");
+        assert_eq!(
+            game.ask("write on paper"),
+            "What do you want to write on the paper?"
+        );
+        assert_eq!(game.ask("'Carol'"), "Written.");
+        assert_eq!(game.ask("x paper"), "Written on the paper is \"Carol\".");
+
+        let save = game.save_to_string().unwrap();
+        game.ask("write Dave on paper");
+        game.restore_from_str(&save).unwrap();
+        assert_eq!(game.ask("x paper"), "Written on the paper is \"Carol\".");
+    }
+
+    #[test]
+    fn triggers() {
+        let mut game: Game = r#"(player: "PLAYER", items: {
+            "PLAYER": (locations: ["DECK"], hp: 10),
+            "DECK": (
+                names: ["Deck"],
+                triggers: [(phrase: "xyzzy", response: "Poof!", go: "HOLD")],
+            ),
+            "HOLD": (names: ["Hold"]),
+            "DOOR": (names: ["door"], container: Closed),
+            "DECK DOOR": (
+                names: ["door"],
+                locations: ["DECK"],
+                dest: "CABIN",
+                door: "DOOR",
+                triggers: [(phrase: "Open Sesame", open: true)],
+            ),
+            "CABIN": (names: ["Cabin"]),
+            "CABIN DOOR": (names: ["door"], locations: ["CABIN"], dest: "DECK", door: "DOOR"),
+            "CAPTAIN": (
+                names: ["captain"],
+                locations: ["CABIN"],
+                hp: 10,
+                nature: Aggressive,
+                triggers: [(phrase: "parley", scope: Addressed, response: "Very well.", nature: Some(Passive))],
+            ),
+            "PARROT": (names: ["parrot"], locations: ["HOLD"]),
+        })"#
+        .parse()
+        .unwrap();
+
+        assert_eq!(game.ask("go door"), "The door is closed.");
+        assert_eq!(
+            game.ask("say 'open, sesame!'"),
+            "You say \"open, sesame!\"."
+        );
+        assert!(game.ask("go door").starts_with("Cabin"));
+
+        // the captain only listens to what is said to him
+        assert!(game.ask("say parley").contains("The captain hits you."));
+        assert_eq!(
+            game.ask("say 'parley' to the captain"),
+            "You say \"parley\".\n\nVery well."
+        );
+        assert_eq!(
+            game.ask("say 'parley' to the parrot"),
+            "You can't see any parrot here."
+        );
+        assert!(!game.ask("wait").contains("hits you"));
+
+        assert!(game.ask("go door").starts_with("Deck"));
+        assert_eq!(
+            game.ask("say xyzzy"),
+            "You say \"xyzzy\".\n\nPoof!\n\nHold\n"
+        );
+
+        // triggers can only send the player to items in the world
+        let err = r#"(player: "PLAYER", items: {
+            "PLAYER": (locations: ["DECK"], hp: 10),
+            "DECK": (triggers: [(phrase: "xyzzy", go: "HOLD")]),
+        })"#
+        .parse::<Game>()
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "3:55: There is no item \"HOLD\" for a trigger of \"DECK\"."
+        );
+    }
+
     #[test]
     fn containers() {
         let mut game: Game = include_str!("world.ron").parse().unwrap();
@@ -145,6 +623,68 @@ mod tests {
             .all(|p| res.contains(p)))
     }
 
+    #[test]
+    fn several_nouns() {
+        let mut game: Game = include_str!("world.ron").parse().unwrap();
+
+        game.ask("open box and take box and n");
+        assert_eq!(
+            game.ask("take sword and block"),
+            "iron sword: Taken.\nlarge red block: Taken."
+        );
+        assert_eq!(
+            game.ask("drop block, sword and look"),
+            "large red block: Dropped.\niron sword: Dropped.\n\nCloset\nYou are in the closet.\nThere is an iron sword here.\nThere is a large red block here."
+        );
+        assert_eq!(
+            game.ask("take sword and spoon"),
+            "iron sword: Taken.\nspoon: You can't see any spoon here."
+        );
+
+        game.ask("take block");
+        assert_eq!(
+            game.ask("put sword and block in box"),
+            "iron sword: Done.\nlarge red block: Done."
+        );
+        assert_eq!(
+            game.ask("take them"),
+            "iron sword: Taken.\nlarge red block: Taken."
+        );
+    }
+
+    #[test]
+    fn all_except() {
+        let mut game: Game = include_str!("world.ron").parse().unwrap();
+
+        assert_eq!(
+            game.ask("take all from box"),
+            "You can't see anything you can take."
+        );
+        game.ask("open box");
+        assert_eq!(game.ask("take all from box"), "apple: Taken.");
+        assert_eq!(
+            game.ask("take all from sword"),
+            "You can't see any sword here."
+        );
+
+        game.ask("n");
+        assert_eq!(game.ask("take all but the block"), "iron sword: Taken.");
+        assert_eq!(
+            game.ask("take everything except sword"),
+            "large red block: Taken."
+        );
+        assert_eq!(
+            game.ask("drop everything except sword and apple"),
+            "large red block: Dropped."
+        );
+        assert!(game
+            .ask("drop all but sword, apple and look")
+            .starts_with("You can't see anything you can drop.\n\nCloset"));
+        let inventory = game.ask("i");
+        assert!(inventory.contains("apple") && inventory.contains("iron sword"));
+        assert!(!inventory.contains("block"));
+    }
+
     #[test]
     fn attack() {
         let mut game: Game = include_str!("world.ron").parse().unwrap();
@@ -182,4 +722,481 @@ mod tests {
         assert_eq!(game.ask("look"), expected);
         assert_eq!(game.ask("again"), expected);
     }
+
+    #[test]
+    fn save() {
+        let mut game: Game = include_str!("world.ron").parse().unwrap();
+
+        game.ask("open box and take apple and enter arena and take spear");
+        game.ask("kill goblin with spear and kill goblin with spear");
+        let save = game.save_to_string().unwrap();
+
+        // only changes are saved
+        assert!(!save.contains("You are in a circular arena"));
+        assert!(save.contains("GOBLIN"));
+
+        let mut restored: Game = include_str!("world.ron").parse().unwrap();
+        restored.restore_from_str(&save).unwrap();
+        assert_eq!(restored.ask("l"), game.ask("l"));
+        assert_eq!(
+            restored.ask("where goblin"),
+            "You can't see any goblin here."
+        );
+        assert_eq!(restored.ask("take dagger"), "Taken.");
+        assert_eq!(restored.ask("where apple"), "It's here.");
+
+        // a save from a different world is rejected
+        let mut other = Game::default();
+        let before = other.ask("l");
+        assert!(other.restore_from_str(&save).is_err());
+        assert_eq!(other.ask("l"), before);
+
+        // a Game deserialized without its world saves changes to its own state
+        let state = ron::to_string(&game).unwrap();
+        let mut deserialized: Game = ron::from_str(&state).unwrap();
+        deserialized.ask("take dagger");
+        let save = deserialized.save_to_string().unwrap();
+        assert!(save.contains("DAGGER"));
+        assert!(!save.contains("GOBLIN"));
+        let mut restored: Game = ron::from_str(&state).unwrap();
+        restored.restore_from_str(&save).unwrap();
+        assert_eq!(restored.ask("where dagger"), "It's here.");
+
+        // loading from a file with the built-in world or a whole Game from an older version
+        let path = std::env::temp_dir().join("kingslayer-load.save");
+        let path = path.to_str().unwrap();
+        let mut game = Game::default();
+        game.ask("take stick");
+        game.save(path).unwrap();
+        assert_eq!(Game::load(path).unwrap().ask("i"), game.ask("i"));
+        std::fs::write(path, &state).unwrap();
+        assert!(Game::load(path).unwrap().ask("l").starts_with("Arena"));
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn transcript() {
+        let mut game: Game = include_str!("world.ron").parse().unwrap();
+
+        let transcript: Transcript = "> open box\nOpening the box reveals a apple.\n\n> take apple\nTaken.\n\n> eat apple\nDelicious."
+            .parse()
+            .unwrap();
+        assert_eq!(transcript.len(), 3);
+
+        let mismatches = transcript.replay(&mut game).unwrap();
+        assert_eq!(mismatches.len(), 1);
+        assert_eq!(mismatches[0].input, "eat apple");
+        assert_eq!(mismatches[0].actual, "You cannot eat that.");
+        assert_eq!(
+            mismatches[0].to_string(),
+            "> eat apple\n- Delicious.\n+ You cannot eat that."
+        );
+
+        // recorded output has no comments or wildcards
+        let save = game.save_to_string().unwrap();
+        let recorded: Transcript =
+            format!("# save: {save}\n\n> look\n*\n\n> i\n# You are carrying:\n...")
+                .parse()
+                .unwrap();
+        let mismatches = recorded.replay(&mut game).unwrap();
+        assert_eq!(mismatches.len(), 2);
+        assert_eq!(mismatches[1].expected, "# You are carrying:\n...");
+    }
+
+    #[test]
+    fn walkthrough() {
+        kingslayer::test_walkthrough(include_str!("world.ron"), include_str!("walkthrough.txt"))
+            .unwrap();
+
+        // wildcards must still match the rest of the output
+        let err = kingslayer::test_walkthrough(include_str!("world.ron"), "> look\nCenter Room\n*")
+            .unwrap_err();
+        assert!(err
+            .to_string()
+            .ends_with("1 of 1 commands differ from the transcript."));
+        let err = kingslayer::test_walkthrough(
+            include_str!("world.ron"),
+            "> look\n*Room\n...\nThere is a sword here.",
+        )
+        .unwrap_err();
+        let err = err.downcast::<kingslayer::WalkthroughError>().unwrap();
+        assert_eq!(err.mismatches[0].input, "look");
+    }
+
+    #[test]
+    fn play_with() {
+        let mut game: Game = include_str!("world.ron").parse().unwrap();
+
+        let save = std::env::temp_dir().join("kingslayer-play-with.save");
+        let input = format!(
+            "open box\nsave\n{}\ntake apple\nrestore\n{}\nq\nn\ni\nquit\nyes\nl\n",
+            save.display(),
+            save.display()
+        );
+        let mut output = Vec::new();
+        game.play_with(input.as_bytes(), &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+
+        assert!(output.starts_with("Center Room\n"));
+        assert!(output.contains("\n> Filename (kingslayer.save): Saved.\n"));
+        assert!(output.contains("\n> Filename (kingslayer.save): OK\n"));
+        assert!(output.contains("\n> Are you sure you want to quit? (y/n): Ok.\n"));
+        // the apple was put back in the box by restoring
+        assert!(output.contains("\n> Your inventory is empty.\n"));
+        // nothing is read after quitting
+        assert!(output.ends_with("Are you sure you want to quit? (y/n): "));
+    }
+
+    #[test]
+    fn play_batch() {
+        let mut game: Game = include_str!("world.ron").parse().unwrap();
+
+        let mut output = Vec::new();
+        game.play_batch("open box\nq\ni\n".as_bytes(), &mut output)
+            .unwrap();
+        // no prompts and nothing is read after quitting
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "Opening the box reveals a apple.\n\n"
+        );
+        assert!(!game.is_dead());
+    }
+
+    #[test]
+    fn play_json() {
+        use serde_json::Value;
+
+        let mut game: Game = include_str!("world.ron").parse().unwrap();
+
+        let input = concat!(
+            "{\"type\": \"ask\", \"input\": \"open box\", \"id\": 1}\n",
+            "{\"type\": \"save\"}\n",
+            "\n",
+            "{\"type\": \"ask\", \"input\": \"n\"}\n",
+            "{\"type\": \"state\"}\n",
+            "{\"type\": \"jump\"}\n",
+            "{\"type\": \"suggestions\"}\n",
+        );
+        let mut output = Vec::new();
+        game.play_json(input.as_bytes(), &mut output).unwrap();
+        let replies = String::from_utf8(output)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str::<Value>(line).unwrap())
+            .collect::<Vec<_>>();
+
+        assert_eq!(replies.len(), 6);
+        assert_eq!(replies[0]["id"], 1);
+        assert_eq!(replies[0]["output"], "Opening the box reveals a apple.");
+        assert_eq!(replies[0]["room"], "Center Room");
+        assert_eq!(replies[0]["hp"], 10);
+        assert_eq!(replies[0]["turns"], 1);
+        assert_eq!(replies[0]["over"], false);
+        assert_eq!(replies[3]["output"], "");
+        assert_eq!(replies[3]["room"], "Closet");
+        assert!(replies[4]["error"].is_string());
+        assert!(replies[5]["suggestions"]
+            .as_array()
+            .unwrap()
+            .contains(&"look".into()));
+
+        let restore = serde_json::json!({ "type": "restore", "save": replies[1]["save"] });
+        let mut output = Vec::new();
+        game.play_json(format!("{restore}\n").as_bytes(), &mut output)
+            .unwrap();
+        let reply: Value = serde_json::from_slice(&output).unwrap();
+        assert_eq!(reply["output"], "OK");
+        assert_eq!(reply["room"], "Center Room");
+    }
+
+    #[test]
+    fn serve() {
+        use std::{
+            io::{Read, Write},
+            net::{Shutdown, TcpListener, TcpStream},
+            thread,
+        };
+
+        let game: Game = include_str!("world.ron").parse().unwrap();
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        thread::spawn(move || game.serve(listener));
+
+        // each connection plays its own game
+        for _ in 0..2 {
+            let mut stream = TcpStream::connect(addr).unwrap();
+            // telnet negotiation, line editing and different line endings
+            stream
+                .write_all(b"\xff\xfb\x1fopen box\r\ntakk\x7fe apple\r\0save\ni\r\n")
+                .unwrap();
+            stream
+                .write_all(b"drop apple\nrestore\ni\nq\r\ny\r\n")
+                .unwrap();
+
+            let mut output = String::new();
+            stream.read_to_string(&mut output).unwrap();
+
+            assert!(output.starts_with("Center Room\r\n"));
+            assert!(output.contains("> Opening the box reveals a apple.\r\n"));
+            assert!(output.contains("> Taken.\r\n"));
+            assert!(output.contains("> Saved to slot 1.\r\n"));
+            assert_eq!(output.matches("You are carrying:\r\n  a apple").count(), 2);
+            assert!(output.ends_with("Are you sure you want to quit? (y/n): "));
+        }
+
+        // a command the game can't carry out only gets a reply
+        let mut stream = TcpStream::connect(addr).unwrap();
+        stream.write_all(b"give box\r\nlight box\r\n").unwrap();
+        stream.shutdown(Shutdown::Write).unwrap();
+        let mut output = String::new();
+        stream.read_to_string(&mut output).unwrap();
+        assert_eq!(output.matches("> You can't do that.\r\n").count(), 2);
+
+        let mut stream = TcpStream::connect(addr).unwrap();
+        stream.write_all(b"open box\r\n").unwrap();
+        stream.shutdown(Shutdown::Write).unwrap();
+        let mut output = String::new();
+        stream.read_to_string(&mut output).unwrap();
+        assert!(output.contains("> Opening the box reveals a apple.\r\n"));
+    }
+
+    #[test]
+    fn multiplayer() {
+        let mut game: Game = include_str!("multiplayer.ron").parse().unwrap();
+
+        // every player in the world sees what the others do, even before acting
+        assert_eq!(game.ask_as("ALICE", "take sword"), "Taken.");
+        assert_eq!(game.messages("BOB"), ["Alice takes the sword."]);
+        assert!(game.messages("BOB").is_empty());
+        assert_eq!(
+            game.ask_as("BOB", "l"),
+            "Hall\nYou are in a hall.\nAlice is here."
+        );
+        assert_eq!(
+            game.ask_as("ALICE", "l"),
+            "Hall\nYou are in a hall.\nBob is here."
+        );
+        assert_eq!(
+            game.ask_as("BOB", "take sword"),
+            "You can't see any sword here."
+        );
+
+        // aggressive creatures fight back against whoever attacked them
+        assert!(game.ask_as("BOB", "n").starts_with("Den"));
+        assert_eq!(game.messages("ALICE"), ["Bob leaves."]);
+        assert_eq!(game.room_as("BOB"), Some("Den"));
+        assert_eq!(game.room_as("ALICE"), Some("Hall"));
+        // without a player, the Game is played as the world's player
+        assert_eq!(game.room(), "Hall");
+        assert!(game.ask("l").starts_with("Hall"));
+        assert!(game
+            .ask_as("ALICE", "n")
+            .ends_with("\n\nThe troll hits you."));
+        assert_eq!(
+            game.messages("BOB"),
+            ["Alice arrives.", "The troll hits Alice."]
+        );
+        assert_eq!(
+            game.ask_as("ALICE", "hit troll with sword"),
+            "You hit the troll with your sword.\n\nThe troll hits you."
+        );
+        assert_eq!(
+            game.messages("BOB"),
+            [
+                "Alice hits the troll with the sword.",
+                "The troll hits Alice."
+            ]
+        );
+
+        assert_eq!(
+            game.ask_as("BOB", "wait"),
+            "Time passes...\n\nThe troll hits Alice."
+        );
+        assert_eq!(game.messages("ALICE"), ["The troll hits you."]);
+        assert_eq!(game.hp_as("ALICE"), Some(4));
+        assert_eq!(game.hp_as("BOB"), Some(3));
+        assert_eq!(game.hp(), 4);
+
+        // a player can die while another keeps playing
+        assert!(game.ask_as("ALICE", "s").starts_with("Hall"));
+        assert_eq!(
+            game.ask_as("BOB", "wait"),
+            "Time passes...\n\nThe troll hits you."
+        );
+        assert_eq!(
+            game.ask_as("BOB", "wait"),
+            "Time passes...\n\nThe troll hits you.\n\nYou die."
+        );
+        assert_eq!(game.ask_as("BOB", "s"), "You are dead.");
+        assert_eq!(game.ask_as("ALICE", "l"), "Hall\nYou are in a hall.");
+        assert_eq!(game.hp_as("BOB"), Some(-1));
+        assert_eq!(game.room_as("BOB"), Some("Den"));
+        assert_eq!(game.hp_as("ALICE"), Some(4));
+        assert!(!game.is_dead());
+        assert_eq!(game.hp_as("TROLL"), None);
+
+        assert_eq!(game.ask_as("CAROL", "l"), "There is no player \"CAROL\".");
+        assert_eq!(game.ask_as("TROLL", "l"), "There is no player \"TROLL\".");
+
+        // players must be items
+        let err = include_str!("multiplayer.ron")
+            .replace("\"BOB\"]", "\"CAROL\"]")
+            .parse::<Game>()
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "1:38: There is no item \"CAROL\" to be a player."
+        );
+    }
+
+    #[cfg(feature = "ffi")]
+    #[test]
+    fn ffi_header() {
+        // the header is generated by every build with the feature but only checked in by hand
+        let header = concat!(env!("OUT_DIR"), "/kingslayer.h");
+        assert!(
+            include_str!(concat!(env!("OUT_DIR"), "/kingslayer.h"))
+                == include_str!("../include/kingslayer.h"),
+            "include/kingslayer.h is out of date, copy it from {header}"
+        );
+    }
+
+    #[cfg(all(feature = "ffi", unix))]
+    #[test]
+    fn ffi() {
+        use std::{env, process::Command};
+
+        // the C library is only built on request, in its own target dir to keep its features
+        let target_dir = concat!(env!("CARGO_MANIFEST_DIR"), "/target/ffi");
+        let status = Command::new(env!("CARGO"))
+            .args([
+                "rustc",
+                "--lib",
+                "--features",
+                "ffi",
+                "--crate-type",
+                "cdylib",
+                "--target-dir",
+                target_dir,
+            ])
+            .status()
+            .unwrap();
+        assert!(status.success());
+        let lib_dir = format!("{target_dir}/debug");
+        let exe = env::temp_dir().join("kingslayer-ffi-test");
+
+        let status = Command::new(env::var("CC").unwrap_or_else(|_| "cc".to_owned()))
+            .arg(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/ffi.c"))
+            .arg(concat!("-I", env!("CARGO_MANIFEST_DIR"), "/include"))
+            .arg(format!("-L{lib_dir}"))
+            .arg("-lkingslayer")
+            .arg("-o")
+            .arg(&exe)
+            .status()
+            .unwrap();
+        assert!(status.success());
+
+        let status = Command::new(&exe)
+            .arg(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/world.ron"))
+            .env("LD_LIBRARY_PATH", &lib_dir)
+            .env("DYLD_LIBRARY_PATH", &lib_dir)
+            .status()
+            .unwrap();
+        assert!(status.success());
+    }
+
+    #[cfg(feature = "http")]
+    #[test]
+    fn http() {
+        use serde_json::Value;
+        use std::{
+            io::{Read, Write},
+            net::{SocketAddr, TcpListener, TcpStream},
+            thread,
+            time::Duration,
+        };
+
+        fn request(addr: SocketAddr, method: &str, path: &str, body: &str) -> (u16, Value) {
+            let mut stream = TcpStream::connect(addr).unwrap();
+            write!(
+                stream,
+                "{method} {path} HTTP/1.1\r\nContent-Length: {}\r\n\r\n{body}",
+                body.len()
+            )
+            .unwrap();
+
+            let mut res = String::new();
+            stream.read_to_string(&mut res).unwrap();
+            let (head, body) = res.split_once("\r\n\r\n").unwrap();
+            (
+                head[9..12].parse().unwrap(),
+                serde_json::from_str(body).unwrap_or(Value::Null),
+            )
+        }
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        thread::spawn(move || kingslayer::serve_http(listener, Duration::from_secs(60)));
+
+        let (status, created) = request(addr, "POST", "/sessions", include_str!("world.ron"));
+        assert_eq!(status, 201);
+        assert_eq!(created["room"], "Center Room");
+        assert_eq!(created["hp"], 10);
+        assert!(created["output"]
+            .as_str()
+            .unwrap()
+            .starts_with("Center Room"));
+        let session = format!("/sessions/{}", created["session"].as_str().unwrap());
+
+        let (status, res) = request(
+            addr,
+            "POST",
+            &format!("{session}/commands"),
+            r#"{"input": "open box"}"#,
+        );
+        assert_eq!(status, 200);
+        assert_eq!(res["output"], "Opening the box reveals a apple.");
+        assert_eq!(res["turns"], 2);
+        assert_eq!(res["over"], false);
+
+        let (_, save) = request(addr, "POST", &format!("{session}/save"), "");
+        request(
+            addr,
+            "POST",
+            &format!("{session}/commands"),
+            r#"{"input": "n"}"#,
+        );
+        assert_eq!(request(addr, "GET", &session, "").1["room"], "Closet");
+        let (status, res) = request(
+            addr,
+            "POST",
+            &format!("{session}/restore"),
+            &save.to_string(),
+        );
+        assert_eq!(status, 200);
+        assert_eq!(res["room"], "Center Room");
+
+        // a command the game can't carry out doesn't hold up other requests
+        let (status, res) = request(
+            addr,
+            "POST",
+            &format!("{session}/commands"),
+            r#"{"input": "give box"}"#,
+        );
+        assert_eq!(status, 200);
+        assert_eq!(res["output"], "You can't do that.");
+        assert_eq!(request(addr, "GET", &session, "").0, 200);
+
+        assert_eq!(request(addr, "POST", "/sessions", "(").0, 400);
+        let mut stream = TcpStream::connect(addr).unwrap();
+        stream
+            .write_all(b"POST /sessions HTTP/1.1\r\nContent-Length: many\r\n\r\n")
+            .unwrap();
+        let mut res = String::new();
+        stream.read_to_string(&mut res).unwrap();
+        assert!(res.starts_with("HTTP/1.1 400 Bad Request\r\n"));
+
+        assert_eq!(request(addr, "DELETE", &session, "").0, 204);
+        assert_eq!(request(addr, "GET", &session, "").0, 404);
+    }
 }